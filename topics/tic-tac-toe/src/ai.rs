@@ -1,131 +1,899 @@
 //! AI module - Minimax algorithm implementation
 
-use crate::board::{Board, Cell};
+use crate::board::{Board, BoardError, Cell, RuleSet};
+#[cfg(feature = "std")]
+use crate::database::PositionDatabase;
+#[cfg(feature = "std")]
+use crate::opening::OpeningBook;
+use crate::rng::Rng;
+#[cfg(feature = "std")]
+use crate::solver::TranspositionTable;
+use crate::solver::{self, Misere, TwoPlayerGame};
+use alloc::vec::Vec;
+use core::cell::RefCell;
+use core::time::Duration;
+#[cfg(feature = "std")]
+use std::time::Instant;
+
+/// How a move compares to the best move available in the same position
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum MoveQuality {
+    /// The move scores as well as any other move
+    Best,
+    /// The move keeps the same outcome but takes longer to win or loses faster
+    Inaccuracy,
+    /// The move worsens the outcome (e.g. turns a draw into a loss)
+    Blunder,
+}
+
+/// How strongly the AI plays
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum Difficulty {
+    /// Plays a uniformly random empty cell
+    Easy,
+    /// Takes an immediate win, otherwise blocks an immediate loss, otherwise plays a random
+    /// empty cell; never looks further ahead
+    Novice,
+    /// Plays the best move about half the time and a random empty cell otherwise
+    Medium,
+    /// Always plays the best move found by full minimax search
+    #[default]
+    Hard,
+    /// Plays as well as `Hard`, but picks at random among all moves with the best score
+    /// instead of breaking ties the same way every time
+    Balanced,
+}
+
+/// Which side can force a win from the start of a game with perfect play
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Advantage {
+    FirstPlayerWins,
+    SecondPlayerWins,
+    Drawn,
+}
+
+/// Measurements of a single move decision
+#[cfg(feature = "std")]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub struct SearchStats {
+    /// Positions the search entered, cached or not, below the board the move was chosen
+    /// on; moves answered by symmetry, the opening book or a random choice add none
+    pub nodes_visited: u64,
+    /// Wall-clock time taken to choose the move
+    pub elapsed: Duration,
+}
+
+/// A tic-tac-toe position together with the mark to move, searchable by the generic solver
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Position {
+    pub board: Board,
+    pub to_move: Cell,
+}
+
+impl Position {
+    /// Largest board whose positions fit a `key`
+    const MAX_KEYED_CELLS: usize = 36;
+
+    /// Encodes the board size and win length, then the cells as a base-3 number (empty 0,
+    /// X 1, O 2 per cell, in reading order), then a bit for the mark to move
+    /// Returns None if the board has too many cells
+    /// Positions get equal keys only when they are equal, whatever their board settings
+    pub(crate) fn key(&self) -> Option<u64> {
+        let size = self.board.size();
+        if size * size > Self::MAX_KEYED_CELLS {
+            return None;
+        }
+
+        // Both are at most 6, so the settings take one of 36 values; cells get a fixed
+        // number of digits so boards of different sizes can't overlap
+        let max_side = Self::MAX_KEYED_CELLS.isqrt();
+        let settings = ((size - 1) * max_side + self.board.win_length() - 1) as u64;
+        let cells = self.board.iter_cells().fold(0u64, |key, (_, _, cell)| {
+            key * 3 + u64::from(cell.base3_digit())
+        });
+        let position = settings * 3u64.pow(Self::MAX_KEYED_CELLS as u32) + cells;
+        Some(position * 2 + u64::from(self.to_move == Cell::O))
+    }
+}
+
+impl TwoPlayerGame for Position {
+    type Move = (usize, usize);
+
+    fn moves(&self) -> Vec<(usize, usize)> {
+        self.board.empty_positions()
+    }
+
+    fn apply(&self, (row, col): (usize, usize)) -> Self {
+        let mut board = self.board.clone();
+        board.set(row, col, self.to_move);
+        Self {
+            board,
+            to_move: self.to_move.opponent(),
+        }
+    }
+
+    fn terminal_value(&self) -> Option<i32> {
+        // `winning_line` walks the board in place, which matters at every node
+        if self.board.winning_line().is_some() {
+            // The player who just moved completed the line. Faster wins leave more
+            // empty cells, so they score higher for the winner
+            Some(-(100 + self.board.remaining_plies() as i32))
+        } else if self.board.is_full() {
+            Some(0)
+        } else {
+            None
+        }
+    }
+}
+
+/// A position in the gravity variant, where marks drop to the lowest empty row of a column
+struct DropPosition {
+    board: Board,
+    to_move: Cell,
+}
+
+impl TwoPlayerGame for DropPosition {
+    type Move = (usize, usize);
+
+    fn moves(&self) -> Vec<(usize, usize)> {
+        self.board.landing_positions()
+    }
+
+    fn apply(&self, (row, col): (usize, usize)) -> Self {
+        let mut board = self.board.clone();
+        board.set(row, col, self.to_move);
+        Self {
+            board,
+            to_move: self.to_move.opponent(),
+        }
+    }
+
+    fn terminal_value(&self) -> Option<i32> {
+        // Gravity only limits which cells can be played, so games end the same way
+        Position {
+            board: self.board.clone(),
+            to_move: self.to_move,
+        }
+        .terminal_value()
+    }
+}
 
 /// AI agent that uses minimax algorithm to determine optimal moves
-pub struct AiAgent;
+/// Random choices made below `Difficulty::Hard` draw from an internal generator started
+/// from a fixed seed, or the one given to `with_seed`, so equally seeded agents shown the same
+/// boards make the same choices
+pub struct AiAgent {
+    difficulty: Difficulty,
+    /// Plies searched ahead, counting the agent's own move, or None to search to the end
+    max_depth: Option<usize>,
+    /// Thinking time after which the search stops deepening, or None to search without one
+    time_limit: Option<Duration>,
+    /// Whether the first plies on a 3x3 board are answered from the opening book
+    #[cfg(feature = "std")]
+    opening_book: bool,
+    /// Whether completing a line wins or loses
+    rules: RuleSet,
+    rng: RefCell<Rng>,
+    /// Scores of positions already searched, shared by every search the agent runs
+    #[cfg(feature = "std")]
+    table: RefCell<TranspositionTable<u64>>,
+    /// Positions searched so far, counted as the solver enters each one
+    nodes_visited: core::cell::Cell<u64>,
+}
 
 impl AiAgent {
-    /// Creates a new AI agent
+    /// Seed of the generator behind random move choices
+    const DEFAULT_SEED: u64 = 0;
+    /// Largest heuristic score of a depth-limited search, below every decisive score
+    const MAX_HEURISTIC: i32 = 99;
+    /// Positions with at most this many empty cells are searched to the end despite a
+    /// time limit, since that takes no noticeable time; this covers every 3x3 position
+    #[cfg(feature = "std")]
+    const ALWAYS_SOLVED_CELLS: usize = 9;
+
+    /// Creates a new AI agent that plays at `Difficulty::Hard`
     pub fn new() -> Self {
-        Self
+        Self::with_difficulty(Difficulty::Hard)
+    }
+
+    /// Creates a new AI agent that plays at the given difficulty
+    pub fn with_difficulty(difficulty: Difficulty) -> Self {
+        Self {
+            difficulty,
+            max_depth: None,
+            time_limit: None,
+            #[cfg(feature = "std")]
+            opening_book: false,
+            rules: RuleSet::Standard,
+            rng: RefCell::new(Rng::new(Self::DEFAULT_SEED)),
+            #[cfg(feature = "std")]
+            table: RefCell::new(TranspositionTable::new()),
+            nodes_visited: core::cell::Cell::new(0),
+        }
+    }
+
+    /// Creates a new `Difficulty::Hard` agent whose random choices are seeded with `seed`
+    /// Change the difficulty with `set_difficulty` to make the seed matter
+    pub fn with_seed(seed: u64) -> Self {
+        Self {
+            rng: RefCell::new(Rng::new(seed)),
+            ..Self::new()
+        }
+    }
+
+    /// Creates a new `Difficulty::Hard` agent that looks at most `depth` plies ahead,
+    /// counting its own move, and judges unfinished positions with `Board::heuristic_score`
+    /// Makes large boards playable; wins and losses inside the horizon are still found
+    pub fn with_max_depth(depth: usize) -> Self {
+        Self {
+            max_depth: Some(depth),
+            ..Self::new()
+        }
+    }
+
+    /// Creates a new `Difficulty::Hard` agent that deepens its search one ply at a time
+    /// and plays the best move of the deepest search finished once `limit` has passed
+    /// The limit is checked between depths, so the last search may run past it
+    /// Needs the `std` feature, for its clock
+    #[cfg(feature = "std")]
+    pub fn with_time_limit(limit: Duration) -> Self {
+        Self {
+            time_limit: Some(limit),
+            ..Self::new()
+        }
+    }
+
+    /// Returns the thinking time the agent stops deepening after, if any
+    pub fn time_limit(&self) -> Option<Duration> {
+        self.time_limit
+    }
+
+    /// Returns how many plies the agent searches ahead, or None if it searches to the end
+    pub fn max_depth(&self) -> Option<usize> {
+        self.max_depth
+    }
+
+    /// Returns the difficulty the agent plays at
+    pub fn difficulty(&self) -> Difficulty {
+        self.difficulty
+    }
+
+    /// Changes the difficulty the agent plays at
+    pub fn set_difficulty(&mut self, difficulty: Difficulty) {
+        self.difficulty = difficulty;
+    }
+
+    /// Returns true if the agent answers the first plies from the opening book
+    #[cfg(feature = "std")]
+    pub fn uses_opening_book(&self) -> bool {
+        self.opening_book
+    }
+
+    /// Turns the opening book on or off
+    /// With it on, the first move and the reply to it on a 3x3 board are played instantly,
+    /// without searching; the book holds the moves the search would choose
+    /// Needs the `std` feature, which the book is built with
+    #[cfg(feature = "std")]
+    pub fn set_opening_book(&mut self, enabled: bool) {
+        self.opening_book = enabled;
+    }
+
+    /// Returns the rules the agent plays by
+    pub fn rules(&self) -> RuleSet {
+        self.rules
+    }
+
+    /// Changes the rules the agent plays by
+    /// Under `RuleSet::Misere` the agent avoids completing lines and tries to force the
+    /// opponent to complete one
+    pub fn set_rules(&mut self, rules: RuleSet) {
+        self.rules = rules;
+        // Cached scores were found under the old rules
+        #[cfg(feature = "std")]
+        self.table.borrow_mut().clear();
     }
 
     /// Returns the best move for the AI player using minimax algorithm with alpha-beta pruning
     /// Returns None if no moves are available (game is over)
     pub fn get_best_move(&self, board: &Board) -> Option<(usize, usize)> {
-        let empty_positions = board.empty_positions();
+        self.get_best_move_for(board, Cell::O)
+    }
 
-        if empty_positions.is_empty() {
+    /// Like `get_best_move`, also reporting how much searching the decision took
+    #[cfg(feature = "std")]
+    pub fn get_best_move_with_stats(&self, board: &Board) -> (Option<(usize, usize)>, SearchStats) {
+        let start = Instant::now();
+        let nodes_before = self.nodes_visited.get();
+        let best_move = self.get_best_move(board);
+        let stats = SearchStats {
+            nodes_visited: self.nodes_visited.get() - nodes_before,
+            elapsed: start.elapsed(),
+        };
+        (best_move, stats)
+    }
+
+    /// Returns the move the agent plays for `mark`, using the same search, tie-breaking
+    /// and difficulty as `get_best_move`
+    pub fn get_best_move_for(&self, board: &Board, mark: Cell) -> Option<(usize, usize)> {
+        if self.difficulty == Difficulty::Novice {
+            self.novice_move(board, mark, &board.empty_positions())
+        } else if self.difficulty == Difficulty::Balanced {
+            self.random_best_move(self.score_moves(board, mark))
+        } else if self.plays_randomly() {
+            self.random_move(&board.empty_positions())
+        } else {
+            self.optimal_move(board, mark)
+        }
+    }
+
+    /// Returns the move the agent plays for `mark` in the gravity variant, where marks
+    /// drop to the lowest empty row of a column; the position is the landing cell
+    pub fn get_best_drop_for(&self, board: &Board, mark: Cell) -> Option<(usize, usize)> {
+        if self.difficulty == Difficulty::Novice {
+            self.novice_move(board, mark, &board.landing_positions())
+        } else if self.difficulty == Difficulty::Balanced {
+            self.random_best_move(self.score_drops(board, mark))
+        } else if self.plays_randomly() {
+            self.random_move(&board.landing_positions())
+        } else {
+            self.optimal_drop(board, mark)
+        }
+    }
+
+    /// Decides whether the next move is random rather than searched, by difficulty
+    fn plays_randomly(&self) -> bool {
+        match self.difficulty {
+            Difficulty::Easy => true,
+            Difficulty::Medium => self.rng.borrow_mut().below(2) == 0,
+            Difficulty::Novice | Difficulty::Hard | Difficulty::Balanced => false,
+        }
+    }
+
+    /// Plays the first of `moves` that wins for `mark`, else the first that stops the
+    /// opponent winning, else a random one
+    fn novice_move(
+        &self,
+        board: &Board,
+        mark: Cell,
+        moves: &[(usize, usize)],
+    ) -> Option<(usize, usize)> {
+        let playable = |wins: Vec<(usize, usize)>| wins.into_iter().find(|m| moves.contains(m));
+        playable(board.winning_moves_for(mark))
+            .or_else(|| playable(board.winning_moves_for(mark.opponent())))
+            .or_else(|| self.random_move(moves))
+    }
+
+    /// Picks one of `moves` uniformly at random, or None if there are none
+    fn random_move(&self, moves: &[(usize, usize)]) -> Option<(usize, usize)> {
+        if moves.is_empty() {
             return None;
         }
+        let index = self.rng.borrow_mut().below(moves.len());
+        Some(moves[index])
+    }
 
-        let mut best_score = i32::MIN;
-        let mut best_moves = Vec::new();
+    /// Picks uniformly at random among the moves sharing the highest score
+    fn random_best_move(&self, scored_moves: Vec<((usize, usize), i32)>) -> Option<(usize, usize)> {
+        let best_score = scored_moves.iter().map(|&(_, score)| score).max()?;
+        let best_moves: Vec<(usize, usize)> = scored_moves
+            .into_iter()
+            .filter(|&(_, score)| score == best_score)
+            .map(|(position, _)| position)
+            .collect();
+        self.random_move(&best_moves)
+    }
 
-        for (row, col) in empty_positions {
-            let mut board_copy = board.clone();
-            board_copy.set(row, col, Cell::O);
+    /// Returns the best drop for `mark` by searching the gravity variant, whatever the difficulty
+    pub(crate) fn optimal_drop(&self, board: &Board, mark: Cell) -> Option<(usize, usize)> {
+        Self::pick_best_move(board, self.score_drops(board, mark), mark)
+    }
 
-            let score = Self::minimax_alpha_beta(&board_copy, 0, false, i32::MIN, i32::MAX);
+    /// Scores every landing position for `mark` in the gravity variant
+    /// Searches are never cached, since gravity changes the values of positions
+    fn score_drops(&self, board: &Board, mark: Cell) -> Vec<((usize, usize), i32)> {
+        let position = DropPosition {
+            board: board.clone(),
+            to_move: mark,
+        };
+        let heuristic = |drop: &DropPosition| drop.board.heuristic_score(drop.to_move);
+        position
+            .moves()
+            .into_iter()
+            .map(|m| {
+                let child = position.apply(m);
+                let score = match self.rules {
+                    RuleSet::Standard => self.search(&child, self.max_depth, |_| None, heuristic),
+                    RuleSet::Misere => self.search(
+                        &Misere(child),
+                        self.max_depth,
+                        |_| None,
+                        |drop| -heuristic(&drop.0),
+                    ),
+                };
+                (m, -score)
+            })
+            .collect()
+    }
 
-            if score > best_score {
-                best_score = score;
-                best_moves.clear();
-                best_moves.push((row, col));
-            } else if score == best_score {
-                best_moves.push((row, col));
+    /// Returns the best move for `mark` by full minimax search, whatever the difficulty
+    /// `mark` is the maximizing side, so this serves either player
+    pub(crate) fn optimal_move(&self, board: &Board, mark: Cell) -> Option<(usize, usize)> {
+        // The book holds standard replies
+        #[cfg(feature = "std")]
+        if self.opening_book && self.rules == RuleSet::Standard {
+            if let Some(book_move) = OpeningBook::lookup(board, mark) {
+                return Some(book_move);
             }
         }
+        #[cfg(feature = "std")]
+        if let Some(limit) = self.time_limit {
+            let scored_moves = self.score_moves_deepening(board, mark, limit);
+            return Self::pick_best_move(board, scored_moves, mark);
+        }
+        Self::pick_best_move(board, self.score_moves(board, mark), mark)
+    }
+
+    /// Scores every empty position for `mark` by iterative deepening, one ply deeper each
+    /// round, until `limit` has passed or the search reaches the end of the game
+    #[cfg(feature = "std")]
+    fn score_moves_deepening(
+        &self,
+        board: &Board,
+        mark: Cell,
+        limit: Duration,
+    ) -> Vec<((usize, usize), i32)> {
+        let full_depth = board.empty_count();
+        if full_depth <= Self::ALWAYS_SOLVED_CELLS {
+            return self.score_moves(board, mark);
+        }
 
-        // If multiple moves have the same score, prioritize strategically
-        Self::select_strategic_move(&best_moves)
+        let start = Instant::now();
+        let deepest = self
+            .max_depth
+            .map_or(full_depth, |depth| depth.min(full_depth));
+        let mut scored_moves = Vec::new();
+        for depth in 1..=deepest {
+            scored_moves = self.score_moves_to_depth(board, mark, Some(depth));
+            if start.elapsed() >= limit {
+                break;
+            }
+        }
+        scored_moves
     }
 
-    /// Select the most strategic move from equally scored positions
-    /// Priority: center > corners > edges
-    fn select_strategic_move(moves: &[(usize, usize)]) -> Option<(usize, usize)> {
-        if moves.is_empty() {
-            return None;
+    /// Returns the best move for the AI player among positions not listed in `excluded`
+    /// Returns None if every available position is excluded
+    pub fn get_best_move_excluding(
+        &self,
+        board: &Board,
+        excluded: &[(usize, usize)],
+    ) -> Option<(usize, usize)> {
+        let allowed_moves = self
+            .score_moves(board, Cell::O)
+            .into_iter()
+            .filter(|(position, _)| !excluded.contains(position))
+            .collect();
+        Self::pick_best_move(board, allowed_moves, Cell::O)
+    }
+
+    /// Picks the highest scoring move for `mark`, breaking ties by open lines then position
+    pub(crate) fn pick_best_move(
+        board: &Board,
+        scored_moves: Vec<((usize, usize), i32)>,
+        mark: Cell,
+    ) -> Option<(usize, usize)> {
+        let best_score = scored_moves.iter().map(|&(_, score)| score).max()?;
+        let best_moves: Vec<(usize, usize)> = scored_moves
+            .into_iter()
+            .filter(|&(_, score)| score == best_score)
+            .map(|(position, _)| position)
+            .collect();
+
+        // If multiple moves have the same score, keep the ones leaving the most lines open,
+        // then prioritize strategically
+        let flexible_moves = Self::most_flexible_moves(board, &best_moves, mark);
+        Self::select_strategic_move(board.size(), &flexible_moves)
+    }
+
+    /// Lazily scores each candidate move for the AI player, one search per item
+    /// Lets a UI show the AI "considering" its options as each score is computed
+    pub fn evaluate_root_moves<'a>(
+        &'a self,
+        board: &'a Board,
+    ) -> impl Iterator<Item = (usize, usize, i32)> + 'a {
+        board.empty_positions().into_iter().map(move |(row, col)| {
+            let score = self.score_move(board, Cell::O, (row, col), self.max_depth);
+            (row, col, score)
+        })
+    }
+
+    /// Solves the empty board of the given size and win length to find which side, if any,
+    /// has a forced win, X moving first
+    /// Standard 3x3 tic-tac-toe is a draw; shorter win lengths hand the first player a win
+    /// Returns the `BoardError` from `Board::with_win_length` for invalid settings
+    pub fn first_move_advantage(size: usize, win_length: usize) -> Result<Advantage, BoardError> {
+        let board = Board::with_win_length(size, win_length)?;
+
+        Ok(match Self::new().evaluate(&board, Cell::X).signum() {
+            1 => Advantage::FirstPlayerWins,
+            -1 => Advantage::SecondPlayerWins,
+            _ => Advantage::Drawn,
+        })
+    }
+
+    /// Returns the minimax value of `board` for `to_move`, assuming `to_move` plays next
+    /// Positive scores are winning for `to_move`, negative losing and zero a draw
+    pub fn evaluate(&self, board: &Board, to_move: Cell) -> i32 {
+        if let Some(winner) = board.check_winner() {
+            let wins = (winner == to_move) == (self.rules == RuleSet::Standard);
+            return if wins { 100 } else { -100 };
         }
 
-        // Check for center position (1,1)
-        if moves.contains(&(1, 1)) {
-            return Some((1, 1));
+        self.score_moves(board, to_move)
+            .into_iter()
+            .map(|(_, score)| score)
+            .max()
+            .unwrap_or(0)
+    }
+
+    /// Returns every move that achieves the optimal score for `to_move`
+    pub fn best_moves(&self, board: &Board, to_move: Cell) -> Vec<(usize, usize)> {
+        let scored_moves = self.analysis_scores(board, to_move);
+
+        let best_score = match scored_moves.iter().map(|&(_, score)| score).max() {
+            Some(score) => score,
+            None => return Vec::new(),
+        };
+
+        scored_moves
+            .into_iter()
+            .filter(|&(_, score)| score == best_score)
+            .map(|(position, _)| position)
+            .collect()
+    }
+
+    /// Classifies playing `mv` for `to_move` against the best available move
+    /// Returns None if `mv` is not an empty position on the board
+    pub fn classify_move(
+        &self,
+        board: &Board,
+        to_move: Cell,
+        mv: (usize, usize),
+    ) -> Option<MoveQuality> {
+        let scored_moves = self.analysis_scores(board, to_move);
+
+        let best_score = scored_moves.iter().map(|&(_, score)| score).max()?;
+        let (_, score) = scored_moves
+            .into_iter()
+            .find(|&(position, _)| position == mv)?;
+
+        Some(if score == best_score {
+            MoveQuality::Best
+        } else if score.signum() == best_score.signum() {
+            MoveQuality::Inaccuracy
+        } else {
+            MoveQuality::Blunder
+        })
+    }
+
+    /// Returns true if playing `mv` keeps `to_move`'s optimal outcome (win, draw or loss) unchanged
+    /// Returns false for moves that throw the outcome away and for illegal moves
+    pub fn preserves_outcome(&self, board: &Board, to_move: Cell, mv: (usize, usize)) -> bool {
+        let scored_moves = self.analysis_scores(board, to_move);
+
+        let best_score = match scored_moves.iter().map(|&(_, score)| score).max() {
+            Some(score) => score,
+            None => return false,
+        };
+
+        scored_moves
+            .into_iter()
+            .find(|&(position, _)| position == mv)
+            .is_some_and(|(_, score)| score.signum() == best_score.signum())
+    }
+
+    /// Returns true if the position is a draw with best play no matter which side moves next
+    /// Neither side has winning chances left, so the result is settled
+    pub fn is_dead_drawn(&self, board: &Board) -> bool {
+        if board.check_winner().is_some() {
+            return false;
+        }
+        self.evaluate(board, Cell::X) == 0 && self.evaluate(board, Cell::O) == 0
+    }
+
+    /// Returns a line of play for `to_move` that holds at least a draw against optimal opposition
+    /// The plan lists only `to_move`'s moves, assuming the opponent answers with its best move
+    /// Returns None if the opponent can force a win
+    pub fn drawing_plan(&self, board: &Board, to_move: Cell) -> Option<Vec<(usize, usize)>> {
+        if self.evaluate(board, to_move) < 0 {
+            return None;
         }
 
-        // Check for corner positions
-        let corners = [(0, 0), (0, 2), (2, 0), (2, 2)];
-        for corner in corners {
-            if moves.contains(&corner) {
-                return Some(corner);
+        let mut board = board.clone();
+        let mut mark = to_move;
+        let mut plan = Vec::new();
+
+        while !board.is_game_over() {
+            let (row, col) = self.optimal_move(&board, mark)?;
+            board.set(row, col, mark);
+            if mark == to_move {
+                plan.push((row, col));
             }
+            mark = mark.opponent();
         }
 
-        // Return any remaining move (edges)
-        Some(moves[0])
+        Some(plan)
     }
 
-    /// Minimax algorithm with alpha-beta pruning for improved performance
-    fn minimax_alpha_beta(
-        board: &Board,
-        depth: usize,
-        is_maximizing: bool,
-        mut alpha: i32,
-        mut beta: i32,
-    ) -> i32 {
-        // Check for terminal states
+    /// Estimates how likely `mv` is to win for `my_symbol` against an opponent playing at random
+    /// Uses expectimax: `my_symbol` keeps choosing the move with the best winning chances
+    /// while every opponent reply is equally likely
+    /// Returns a probability between 0 and 1, or 0 if `mv` is not an empty position
+    pub fn trap_score(&self, board: &Board, mv: (usize, usize), my_symbol: Cell) -> f32 {
+        let mut board_copy = board.clone();
+        if !board_copy.set(mv.0, mv.1, my_symbol) {
+            return 0.0;
+        }
+        Self::expectimax(&board_copy, my_symbol, my_symbol.opponent())
+    }
+
+    /// Probability that `me` wins from `board` with `to_move` next, against a random opponent
+    fn expectimax(board: &Board, me: Cell, to_move: Cell) -> f32 {
         if let Some(winner) = board.check_winner() {
-            return match winner {
-                Cell::O => 100 - depth as i32, // AI wins (prefer shorter paths to victory)
-                Cell::X => depth as i32 - 100, // Human wins (prefer longer paths to defeat)
-                Cell::Empty => 0,              // Should never happen in practice
-            };
+            return if winner == me { 1.0 } else { 0.0 };
         }
 
-        // If board is full, it's a draw
-        if board.is_full() {
-            return 0;
+        let empty_positions = board.empty_positions();
+        if empty_positions.is_empty() {
+            return 0.0;
         }
 
-        if is_maximizing {
-            // AI's turn - maximize score
-            let mut max_score = i32::MIN;
+        let values = empty_positions.iter().map(|&(row, col)| {
+            let mut board_copy = board.clone();
+            board_copy.set(row, col, to_move);
+            Self::expectimax(&board_copy, me, to_move.opponent())
+        });
 
-            for (row, col) in board.empty_positions() {
-                let mut board_copy = board.clone();
-                board_copy.set(row, col, Cell::O);
+        if to_move == me {
+            values.fold(0.0, f32::max)
+        } else {
+            values.sum::<f32>() / empty_positions.len() as f32
+        }
+    }
 
-                let score = Self::minimax_alpha_beta(&board_copy, depth + 1, false, alpha, beta);
-                max_score = max_score.max(score);
-                alpha = alpha.max(score);
+    /// Rates how hard it is for `to_move` to find the right move on `board`
+    /// Combines how many moves throw away the optimal outcome (fewer good moves = harder)
+    /// with how many plies remain until the game is resolved
+    /// Returns 0 when there is nothing to play
+    pub fn position_difficulty(&self, board: &Board, to_move: Cell) -> u32 {
+        let scored_moves = self.analysis_scores(board, to_move);
 
-                // Alpha-beta pruning
-                if beta <= alpha {
-                    break;
-                }
-            }
+        let best_score = match scored_moves.iter().map(|&(_, score)| score).max() {
+            Some(score) => score,
+            None => return 0,
+        };
+
+        let preserving = scored_moves
+            .iter()
+            .filter(|&&(_, score)| score.signum() == best_score.signum())
+            .count();
+        let losing_choices = (scored_moves.len() - preserving) as u32;
 
-            max_score
+        // Decisive scores encode their depth; draws run until the board is full
+        let plies_to_resolution = if best_score == 0 {
+            scored_moves.len() as u32
         } else {
-            // Human's turn - minimize score
-            let mut min_score = i32::MAX;
+            (100 - best_score.unsigned_abs()) + 1
+        };
 
-            for (row, col) in board.empty_positions() {
+        losing_choices * 10 + plies_to_resolution
+    }
+
+    /// Scores every empty position for `to_move` like `score_moves`, reading standard 3x3
+    /// positions from the `PositionDatabase` instead of searching them
+    fn analysis_scores(&self, board: &Board, to_move: Cell) -> Vec<((usize, usize), i32)> {
+        #[cfg(feature = "std")]
+        if self.rules == RuleSet::Standard && self.max_depth.is_none() {
+            if let Some(scored_moves) = PositionDatabase::move_scores(board, to_move) {
+                return scored_moves;
+            }
+        }
+        self.score_moves(board, to_move)
+    }
+
+    /// Scores every empty position for `mark`, assuming `mark` moves next
+    /// Higher scores are better for `mark`
+    /// Moves that a symmetry of the board maps onto an already scored move share its score
+    /// instead of being searched again
+    pub(crate) fn score_moves(&self, board: &Board, mark: Cell) -> Vec<((usize, usize), i32)> {
+        self.score_moves_to_depth(board, mark, self.max_depth)
+    }
+
+    /// Like `score_moves`, searching `depth` plies ahead instead of the agent's own limit
+    fn score_moves_to_depth(
+        &self,
+        board: &Board,
+        mark: Cell,
+        depth: Option<usize>,
+    ) -> Vec<((usize, usize), i32)> {
+        let preserving: Vec<usize> = (1..Board::SYMMETRY_COUNT)
+            .filter(|&symmetry| board.transformed(symmetry) == *board)
+            .collect();
+
+        let mut scored_moves: Vec<((usize, usize), i32)> = Vec::new();
+        for position in board.empty_positions() {
+            let known_score = preserving.iter().find_map(|&symmetry| {
+                let image = board.transform_position(symmetry, position);
+                scored_moves
+                    .iter()
+                    .find(|&&(scored, _)| scored == image)
+                    .map(|&(_, score)| score)
+            });
+            let score =
+                known_score.unwrap_or_else(|| self.score_move(board, mark, position, depth));
+            scored_moves.push((position, score));
+        }
+        scored_moves
+    }
+
+    /// Scores playing `mark` at `(row, col)` from `mark`'s perspective
+    fn score_move(
+        &self,
+        board: &Board,
+        mark: Cell,
+        (row, col): (usize, usize),
+        depth: Option<usize>,
+    ) -> i32 {
+        let mut board_copy = board.clone();
+        board_copy.set(row, col, mark);
+        self.minimax_alpha_beta(&board_copy, mark, depth)
+    }
+
+    /// Keeps the moves after which `mark` has the most lines it can still complete
+    fn most_flexible_moves(
+        board: &Board,
+        moves: &[(usize, usize)],
+        mark: Cell,
+    ) -> Vec<(usize, usize)> {
+        let open_lines: Vec<usize> = moves
+            .iter()
+            .map(|&(row, col)| {
                 let mut board_copy = board.clone();
-                board_copy.set(row, col, Cell::X);
+                board_copy.set(row, col, mark);
+                board_copy.open_line_count(mark)
+            })
+            .collect();
 
-                let score = Self::minimax_alpha_beta(&board_copy, depth + 1, true, alpha, beta);
-                min_score = min_score.min(score);
-                beta = beta.min(score);
+        let most_open = open_lines.iter().copied().max().unwrap_or(0);
+        moves
+            .iter()
+            .zip(open_lines)
+            .filter(|&(_, open)| open == most_open)
+            .map(|(&position, _)| position)
+            .collect()
+    }
 
-                // Alpha-beta pruning
-                if beta <= alpha {
-                    break;
-                }
-            }
+    /// Select the most strategic move from equally scored positions on a board of `size`
+    /// Priority: center (the middle cell, or the middle four on even sizes) > corners > edges
+    pub(crate) fn select_strategic_move(
+        size: usize,
+        moves: &[(usize, usize)],
+    ) -> Option<(usize, usize)> {
+        let middle = [(size - 1) / 2, size / 2];
+        let last = size - 1;
+        let corners = [(0, 0), (0, last), (last, 0), (last, last)];
+
+        moves
+            .iter()
+            .find(|&&(row, col)| middle.contains(&row) && middle.contains(&col))
+            .or_else(|| moves.iter().find(|position| corners.contains(position)))
+            .or_else(|| moves.first())
+            .copied()
+    }
+
+    /// Minimax search with alpha-beta pruning, delegated to the generic solver
+    /// Scores `board` from the perspective of `mark`, who has just moved
+    /// Boards small enough to key are searched through the agent's transposition table,
+    /// unless the search is limited to `depth` plies
+    fn minimax_alpha_beta(&self, board: &Board, mark: Cell, depth: Option<usize>) -> i32 {
+        let position = Position {
+            board: board.clone(),
+            to_move: mark.opponent(),
+        };
+        let heuristic = |position: &Position| position.board.heuristic_score(position.to_move);
+        let score = -match self.rules {
+            RuleSet::Standard => self.search(
+                &Counting {
+                    game: position,
+                    nodes: &self.nodes_visited,
+                },
+                depth,
+                |counting| counting.game.key(),
+                |counting| heuristic(&counting.game),
+            ),
+            RuleSet::Misere => self.search(
+                &Counting {
+                    game: Misere(position),
+                    nodes: &self.nodes_visited,
+                },
+                depth,
+                |counting| counting.game.0.key(),
+                |counting| -heuristic(&counting.game.0),
+            ),
+        };
+
+        // Position rewards wins by the cells left empty; rescale so decisive scores
+        // count plies from this board instead (100 for a win on the board itself)
+        let empty_count = board.empty_count() as i32;
+        if score > Self::MAX_HEURISTIC {
+            score - empty_count
+        } else if score < -Self::MAX_HEURISTIC {
+            score + empty_count
+        } else {
+            score
+        }
+    }
+}
+
+/// Wraps a game so the agent counts every position its search enters
+struct Counting<'a, G> {
+    game: G,
+    nodes: &'a core::cell::Cell<u64>,
+}
+
+impl<G: TwoPlayerGame> TwoPlayerGame for Counting<'_, G> {
+    type Move = G::Move;
 
-            min_score
+    fn moves(&self) -> Vec<G::Move> {
+        self.game.moves()
+    }
+
+    fn apply(&self, m: G::Move) -> Self {
+        Self {
+            game: self.game.apply(m),
+            nodes: self.nodes,
+        }
+    }
+
+    /// The solver asks this first, exactly once, for every position it enters
+    fn terminal_value(&self) -> Option<i32> {
+        self.nodes.set(self.nodes.get() + 1);
+        self.game.terminal_value()
+    }
+}
+
+impl AiAgent {
+    /// Scores `position` for the player to move, searching `depth` plies counting the move
+    /// that led here, or to the end if None
+    /// `heuristic` judges unfinished positions at the horizon, and positions with a `key`
+    /// are cached in the agent's transposition table unless the search is depth limited
+    fn search<G: TwoPlayerGame>(
+        &self,
+        position: &G,
+        depth: Option<usize>,
+        key: impl Fn(&G) -> Option<u64>,
+        heuristic: impl Fn(&G) -> i32,
+    ) -> i32 {
+        match (depth, key(position)) {
+            // The move leading here used up one ply of the horizon. Heuristic scores stay
+            // below every decisive score, so a forced result always outweighs them
+            (Some(depth), _) => solver::negamax_limited(
+                position,
+                depth.saturating_sub(1),
+                -i32::MAX,
+                i32::MAX,
+                &|position: &G| {
+                    heuristic(position).clamp(-Self::MAX_HEURISTIC, Self::MAX_HEURISTIC)
+                },
+            ),
+            // Every position in the search has the same board size, so all of them have keys
+            #[cfg(feature = "std")]
+            (None, Some(_)) => solver::minimax_cached(
+                position,
+                &|position: &G| key(position).unwrap_or_default(),
+                &mut self.table.borrow_mut(),
+            ),
+            (None, _) => solver::minimax(position),
         }
     }
 }
@@ -139,6 +907,7 @@ impl Default for AiAgent {
 #[cfg(test)]
 mod tests {
     use super::*;
+    use alloc::vec;
 
     #[test]
     fn test_ai_agent_creation() {
@@ -201,15 +970,24 @@ mod tests {
     fn test_strategic_move_selection() {
         // Test center preference
         let moves = vec![(0, 1), (1, 1), (2, 1)];
-        assert_eq!(AiAgent::select_strategic_move(&moves), Some((1, 1)));
+        assert_eq!(AiAgent::select_strategic_move(3, &moves), Some((1, 1)));
 
         // Test corner preference when no center
         let moves = vec![(0, 1), (0, 0), (2, 1)];
-        assert_eq!(AiAgent::select_strategic_move(&moves), Some((0, 0)));
+        assert_eq!(AiAgent::select_strategic_move(3, &moves), Some((0, 0)));
 
         // Test edge selection when no center or corners
         let moves = vec![(0, 1), (1, 0), (2, 1)];
-        assert_eq!(AiAgent::select_strategic_move(&moves), Some((0, 1)));
+        assert_eq!(AiAgent::select_strategic_move(3, &moves), Some((0, 1)));
+
+        // Larger boards use their own center and corners
+        let moves = vec![(0, 1), (1, 1), (2, 2)];
+        assert_eq!(AiAgent::select_strategic_move(5, &moves), Some((2, 2)));
+        let moves = vec![(0, 1), (2, 2), (3, 3)];
+        assert_eq!(AiAgent::select_strategic_move(4, &moves), Some((2, 2)));
+        let moves = vec![(0, 1), (0, 2), (0, 3)];
+        assert_eq!(AiAgent::select_strategic_move(4, &moves), Some((0, 3)));
+        assert_eq!(AiAgent::select_strategic_move(4, &[]), None);
     }
 
     #[test]
@@ -226,7 +1004,7 @@ mod tests {
 
         // AI should block one of the winning paths
         // Valid blocking moves: (0,2), (2,0), (0,1), (1,0), (1,2), (2,1)
-        let blocking_moves = vec![(0, 2), (2, 0), (0, 1), (1, 0), (1, 2), (2, 1)];
+        let blocking_moves = [(0, 2), (2, 0), (0, 1), (1, 0), (1, 2), (2, 1)];
         assert!(blocking_moves.contains(&best_move.unwrap()));
     }
 
@@ -264,7 +1042,665 @@ mod tests {
         let ai = AiAgent::new();
         let best_move = ai.get_best_move(&board);
         // Should take opposite corner (2,2) or another strategic position
-        let strategic_moves = vec![(2, 2), (0, 2), (2, 0)];
+        let strategic_moves = [(2, 2), (0, 2), (2, 0)];
         assert!(strategic_moves.contains(&best_move.unwrap()));
     }
+
+    #[test]
+    fn test_position_difficulty_unique_win_is_harder() {
+        // O's only winning move is (0, 2); anything else lets X win at (1, 2)
+        let mut unique = Board::new();
+        unique.set(0, 0, Cell::O);
+        unique.set(0, 1, Cell::O);
+        unique.set(1, 0, Cell::X);
+        unique.set(1, 1, Cell::X);
+        unique.set(2, 2, Cell::X);
+
+        // O wins at (2, 2) right away, and most other moves still force a win
+        let mut many = Board::new();
+        many.set(0, 0, Cell::O);
+        many.set(1, 1, Cell::O);
+        many.set(0, 1, Cell::X);
+        many.set(1, 0, Cell::X);
+
+        let ai = AiAgent::new();
+        assert!(ai.position_difficulty(&unique, Cell::O) > ai.position_difficulty(&many, Cell::O));
+    }
+
+    #[test]
+    fn test_evaluate_is_symmetric_under_swap() {
+        let mut board = Board::new();
+        board.set(0, 0, Cell::X);
+        board.set(1, 1, Cell::O);
+        board.set(2, 2, Cell::X);
+        board.set(0, 1, Cell::O);
+
+        let ai = AiAgent::new();
+        assert_eq!(
+            ai.evaluate(&board, Cell::X),
+            ai.evaluate(&board.swap_players(), Cell::O)
+        );
+        assert_eq!(
+            ai.evaluate(&board, Cell::O),
+            ai.evaluate(&board.swap_players(), Cell::X)
+        );
+        assert_eq!(ai.evaluate(&Board::new(), Cell::X), 0);
+    }
+
+    #[test]
+    fn test_classify_move() {
+        // O must block at (0, 2); anything else hands X the win
+        let mut board = Board::new();
+        board.set(0, 0, Cell::X);
+        board.set(0, 1, Cell::X);
+        board.set(1, 1, Cell::O);
+
+        let ai = AiAgent::new();
+        assert_eq!(ai.best_moves(&board, Cell::O), vec![(0, 2)]);
+        assert_eq!(
+            ai.classify_move(&board, Cell::O, (0, 2)),
+            Some(MoveQuality::Best)
+        );
+        assert_eq!(
+            ai.classify_move(&board, Cell::O, (2, 2)),
+            Some(MoveQuality::Blunder)
+        );
+        assert_eq!(ai.classify_move(&board, Cell::O, (0, 0)), None);
+    }
+
+    #[test]
+    fn test_ai_prefers_open_lines_among_tied_moves() {
+        let mut board = Board::new();
+        board.set(0, 1, Cell::X);
+        board.set(1, 0, Cell::O);
+        board.set(1, 2, Cell::X);
+
+        let ai = AiAgent::new();
+        // Both corners on the right hold the draw, but (2, 2) keeps three O lines open
+        // against two for (0, 2), which the corner ordering alone would have picked
+        assert_eq!(ai.best_moves(&board, Cell::O), vec![(0, 2), (2, 2)]);
+        assert_eq!(ai.get_best_move(&board), Some((2, 2)));
+    }
+
+    #[test]
+    fn test_best_move_excluding() {
+        let ai = AiAgent::new();
+        let board = Board::new();
+
+        // With the center forbidden, the next best opening is a corner
+        assert_eq!(ai.get_best_move_excluding(&board, &[]), Some((1, 1)));
+        assert_eq!(ai.get_best_move_excluding(&board, &[(1, 1)]), Some((0, 0)));
+        assert_eq!(
+            ai.get_best_move_excluding(&board, &[(1, 1), (0, 0)]),
+            Some((0, 2))
+        );
+
+        let everything = board.empty_positions();
+        assert_eq!(ai.get_best_move_excluding(&board, &everything), None);
+    }
+
+    #[test]
+    fn test_drawing_plan_holds_against_best_play() {
+        // Against a corner opening only the center holds the draw
+        let mut board = Board::new();
+        board.set(0, 0, Cell::X);
+
+        let ai = AiAgent::new();
+        let plan = ai.drawing_plan(&board, Cell::O).unwrap();
+        assert_eq!(plan[0], (1, 1));
+
+        // Follow the plan while X keeps playing its best moves
+        let mut plan_moves = plan.into_iter();
+        let mut mark = Cell::O;
+        while !board.is_game_over() {
+            let (row, col) = if mark == Cell::O {
+                plan_moves.next().unwrap()
+            } else {
+                ai.get_best_move_for(&board, Cell::X).unwrap()
+            };
+            assert!(board.set(row, col, mark));
+            mark = mark.opponent();
+        }
+        assert_ne!(board.check_winner(), Some(Cell::X));
+        assert!(plan_moves.next().is_none());
+    }
+
+    #[test]
+    fn test_drawing_plan_none_when_lost() {
+        // X has built a fork: it threatens both (0, 1) and (1, 2)
+        let mut board = Board::new();
+        board.set(0, 0, Cell::X);
+        board.set(2, 2, Cell::X);
+        board.set(0, 2, Cell::X);
+        board.set(1, 1, Cell::O);
+        board.set(2, 0, Cell::O);
+
+        let ai = AiAgent::new();
+        assert_eq!(ai.drawing_plan(&board, Cell::O), None);
+    }
+
+    #[test]
+    fn test_preserves_outcome() {
+        // Against a center opening, corners hold the draw but edges lose
+        let mut board = Board::new();
+        board.set(1, 1, Cell::X);
+
+        let ai = AiAgent::new();
+        assert_eq!(ai.evaluate(&board, Cell::O), 0);
+        assert!(ai.preserves_outcome(&board, Cell::O, (0, 0)));
+        assert!(ai.preserves_outcome(&board, Cell::O, (2, 2)));
+        assert!(!ai.preserves_outcome(&board, Cell::O, (0, 1)));
+        assert!(!ai.preserves_outcome(&board, Cell::O, (1, 1)));
+    }
+
+    #[test]
+    fn test_trap_score_prefers_corner_opening() {
+        let ai = AiAgent::new();
+        let board = Board::new();
+
+        // The corner opening sets more traps for a random opponent than an edge opening
+        let corner = ai.trap_score(&board, (0, 0), Cell::X);
+        let edge = ai.trap_score(&board, (0, 1), Cell::X);
+        assert!(corner > edge);
+        assert!(corner <= 1.0 && edge > 0.0);
+
+        let mut occupied = Board::new();
+        occupied.set(0, 0, Cell::O);
+        assert_eq!(ai.trap_score(&occupied, (0, 0), Cell::X), 0.0);
+    }
+
+    #[test]
+    fn test_is_dead_drawn() {
+        // Mirror-image position: whoever moves, best play draws
+        let mut balanced = Board::new();
+        balanced.set(0, 0, Cell::X);
+        balanced.set(1, 2, Cell::X);
+        balanced.set(0, 2, Cell::O);
+        balanced.set(1, 0, Cell::O);
+
+        let ai = AiAgent::new();
+        assert!(ai.is_dead_drawn(&balanced));
+
+        // After a corner and an adjacent edge, X to move can force a win
+        let mut with_chances = Board::new();
+        with_chances.set(0, 0, Cell::X);
+        with_chances.set(0, 1, Cell::O);
+        assert!(!ai.is_dead_drawn(&with_chances));
+
+        // A won board is decided, not drawn
+        let mut won = Board::new();
+        for col in 0..3 {
+            won.set(0, col, Cell::X);
+        }
+        assert!(!ai.is_dead_drawn(&won));
+    }
+
+    /// Plain minimax without pruning, scoring from `mark`'s perspective with `depth` plies played
+    fn reference_minimax(board: &Board, depth: i32, mark: Cell, to_move: Cell) -> i32 {
+        if let Some(winner) = board.check_winner() {
+            return if winner == mark {
+                100 - depth
+            } else {
+                depth - 100
+            };
+        }
+        if board.is_full() {
+            return 0;
+        }
+
+        let scores = board.empty_positions().into_iter().map(|(row, col)| {
+            let mut board_copy = board.clone();
+            board_copy.set(row, col, to_move);
+            reference_minimax(&board_copy, depth + 1, mark, to_move.opponent())
+        });
+        if to_move == mark {
+            scores.max().unwrap()
+        } else {
+            scores.min().unwrap()
+        }
+    }
+
+    #[test]
+    fn test_solver_matches_reference_minimax() {
+        // One agent throughout, so later searches reuse the transposition table
+        let ai = AiAgent::new();
+        for record in Board::games_of_length(4, true) {
+            let board = record.final_board();
+            for ((row, col), score) in ai.score_moves(&board, Cell::X) {
+                let mut board_copy = board.clone();
+                board_copy.set(row, col, Cell::X);
+                assert_eq!(
+                    score,
+                    reference_minimax(&board_copy, 0, Cell::X, Cell::O),
+                    "move ({}, {}) on\n{}",
+                    row,
+                    col,
+                    board
+                );
+            }
+        }
+    }
+
+    #[test]
+    fn test_evaluate_root_moves_covers_all_moves() {
+        let mut board = Board::new();
+        board.set(0, 0, Cell::X);
+        board.set(0, 1, Cell::X);
+        board.set(1, 1, Cell::O);
+
+        let ai = AiAgent::new();
+        let evaluated: Vec<(usize, usize, i32)> = ai.evaluate_root_moves(&board).collect();
+        assert_eq!(evaluated.len(), board.empty_positions().len());
+
+        let expected: Vec<(usize, usize, i32)> = ai
+            .score_moves(&board, Cell::O)
+            .into_iter()
+            .map(|((row, col), score)| (row, col, score))
+            .collect();
+        assert_eq!(evaluated, expected);
+
+        // Only the block at (0, 2) avoids losing
+        let best = evaluated
+            .iter()
+            .max_by_key(|&&(_, _, score)| score)
+            .unwrap();
+        assert_eq!((best.0, best.1), (0, 2));
+        assert!(evaluated
+            .iter()
+            .filter(|&&(row, col, _)| (row, col) != (0, 2))
+            .all(|&(_, _, score)| score < 0));
+    }
+
+    #[test]
+    fn test_first_move_advantage() {
+        assert_eq!(AiAgent::first_move_advantage(3, 3), Ok(Advantage::Drawn));
+        assert_eq!(
+            AiAgent::first_move_advantage(3, 2),
+            Ok(Advantage::FirstPlayerWins)
+        );
+        // Too slow to solve without the transposition table
+        #[cfg(feature = "std")]
+        assert_eq!(
+            AiAgent::first_move_advantage(4, 3),
+            Ok(Advantage::FirstPlayerWins)
+        );
+        assert_eq!(
+            AiAgent::first_move_advantage(2, 2),
+            Err(BoardError::InvalidSize)
+        );
+    }
+
+    #[test]
+    fn test_difficulty_levels() {
+        assert_eq!(AiAgent::new().difficulty(), Difficulty::Hard);
+        assert_eq!(Difficulty::default(), Difficulty::Hard);
+
+        // X threatens the top row; only (0, 2) blocks it
+        let mut board = Board::new();
+        board.set(0, 0, Cell::X);
+        board.set(0, 1, Cell::X);
+        board.set(1, 1, Cell::O);
+
+        let count_blocks = |difficulty| {
+            let ai = AiAgent::with_difficulty(difficulty);
+            (0..100)
+                .filter(|_| {
+                    let (row, col) = ai.get_best_move(&board).unwrap();
+                    assert!(board.is_empty(row, col));
+                    (row, col) == (0, 2)
+                })
+                .count()
+        };
+
+        let easy = count_blocks(Difficulty::Easy);
+        let medium = count_blocks(Difficulty::Medium);
+        let hard = count_blocks(Difficulty::Hard);
+        assert_eq!(hard, 100);
+        assert!(easy < medium && medium < hard);
+
+        let mut ai = AiAgent::with_difficulty(Difficulty::Easy);
+        ai.set_difficulty(Difficulty::Hard);
+        assert_eq!(ai.get_best_move(&board), Some((0, 2)));
+    }
+
+    #[test]
+    fn test_novice_wins_then_blocks_then_plays_randomly() {
+        let ai = AiAgent::with_difficulty(Difficulty::Novice);
+
+        // O can win the middle row and X threatens the top row; winning comes first
+        let board = Board::from_str_grid("XX.\nOO.\nX..").unwrap();
+        assert_eq!(ai.get_best_move(&board), Some((1, 2)));
+
+        // Without a win of its own, O blocks
+        let board = Board::from_str_grid("XX.\n.O.\n...").unwrap();
+        for _ in 0..10 {
+            assert_eq!(ai.get_best_move(&board), Some((0, 2)));
+        }
+
+        // Quiet positions get varied moves, repeatable by seed
+        let board = Board::from_str_grid("X..\n...\n...").unwrap();
+        let choices = |seed| {
+            let mut ai = AiAgent::with_seed(seed);
+            ai.set_difficulty(Difficulty::Novice);
+            (0..20)
+                .map(|_| ai.get_best_move(&board).unwrap())
+                .collect::<Vec<_>>()
+        };
+        let moves = choices(7);
+        assert!(moves.iter().any(|&m| m != moves[0]));
+        assert_eq!(choices(7), moves);
+    }
+
+    #[test]
+    fn test_balanced_never_loses_but_varies() {
+        let hard = AiAgent::new();
+        let mut first_moves = Vec::new();
+        for seed in 0..10 {
+            let mut balanced = AiAgent::with_seed(seed);
+            balanced.set_difficulty(Difficulty::Balanced);
+
+            // Balanced plays X in even games and O in odd ones
+            let balanced_mark = if seed % 2 == 0 { Cell::X } else { Cell::O };
+            let mut board = Board::new();
+            let mut mark = Cell::X;
+            while !board.is_game_over() {
+                let agent = if mark == balanced_mark {
+                    &balanced
+                } else {
+                    &hard
+                };
+                let (row, col) = agent.get_best_move_for(&board, mark).unwrap();
+                if board.empty_count() == 9 && mark == balanced_mark {
+                    first_moves.push((row, col));
+                }
+                board.set(row, col, mark);
+                mark = mark.opponent();
+            }
+            assert_ne!(board.check_winner(), Some(balanced_mark.opponent()));
+        }
+
+        first_moves.sort();
+        first_moves.dedup();
+        assert!(first_moves.len() > 1);
+    }
+
+    #[test]
+    fn test_fresh_agents_make_the_same_random_choices() {
+        let first = AiAgent::with_difficulty(Difficulty::Easy);
+        let second = AiAgent::with_difficulty(Difficulty::Easy);
+        let board = Board::new();
+        for _ in 0..20 {
+            assert_eq!(first.get_best_move(&board), second.get_best_move(&board));
+        }
+    }
+
+    #[test]
+    fn test_same_seed_plays_the_same_game() {
+        let mut first = AiAgent::with_seed(42);
+        let mut second = AiAgent::with_seed(42);
+        first.set_difficulty(Difficulty::Medium);
+        second.set_difficulty(Difficulty::Medium);
+
+        for _ in 0..5 {
+            let mut board = Board::new();
+            let mut mark = Cell::X;
+            while !board.is_game_over() {
+                let (row, col) = first.get_best_move_for(&board, mark).unwrap();
+                assert_eq!(second.get_best_move_for(&board, mark), Some((row, col)));
+                board.set(row, col, mark);
+                mark = mark.opponent();
+            }
+        }
+
+        // Different seeds lead to different choices
+        let one = AiAgent::with_seed(1);
+        let two = AiAgent::with_seed(2);
+        let mut one_easy = AiAgent::with_seed(1);
+        one_easy.set_difficulty(Difficulty::Easy);
+        let mut two_easy = AiAgent::with_seed(2);
+        two_easy.set_difficulty(Difficulty::Easy);
+        let board = Board::new();
+        assert_eq!(one.get_best_move(&board), two.get_best_move(&board));
+        assert!((0..20).any(|_| one_easy.get_best_move(&board) != two_easy.get_best_move(&board)));
+    }
+
+    /// Wraps a position to count the nodes a search visits
+    #[cfg(feature = "std")]
+    struct Counted<'a> {
+        position: Position,
+        nodes: &'a std::cell::Cell<u64>,
+    }
+
+    #[cfg(feature = "std")]
+    impl TwoPlayerGame for Counted<'_> {
+        type Move = (usize, usize);
+
+        fn moves(&self) -> Vec<(usize, usize)> {
+            self.position.moves()
+        }
+
+        fn apply(&self, m: (usize, usize)) -> Self {
+            self.nodes.set(self.nodes.get() + 1);
+            Self {
+                position: self.position.apply(m),
+                nodes: self.nodes,
+            }
+        }
+
+        fn terminal_value(&self) -> Option<i32> {
+            self.position.terminal_value()
+        }
+    }
+
+    #[test]
+    #[cfg(feature = "std")]
+    fn test_transposition_table_saves_nodes_on_4x4() {
+        let position = Position {
+            board: Board::from_str_grid("XOX.\n.OX.\nO...\n....").unwrap(),
+            to_move: Cell::X,
+        };
+
+        let plain_nodes = std::cell::Cell::new(0);
+        let plain = solver::minimax(&Counted {
+            position: position.clone(),
+            nodes: &plain_nodes,
+        });
+
+        let cached_nodes = std::cell::Cell::new(0);
+        let mut table = TranspositionTable::new();
+        let cached = solver::minimax_cached(
+            &Counted {
+                position,
+                nodes: &cached_nodes,
+            },
+            &|counted: &Counted| counted.position.key().unwrap(),
+            &mut table,
+        );
+
+        assert_eq!(cached, plain);
+        assert!(
+            cached_nodes.get() * 5 < plain_nodes.get(),
+            "cached search visited {} nodes, plain search {}",
+            cached_nodes.get(),
+            plain_nodes.get()
+        );
+    }
+
+    #[test]
+    fn test_position_key() {
+        let board = Board::from_str_grid("X..\n.O.\n...").unwrap();
+        let x_to_move = Position {
+            board: board.clone(),
+            to_move: Cell::X,
+        };
+        let o_to_move = Position {
+            board,
+            to_move: Cell::O,
+        };
+        assert_ne!(x_to_move.key(), o_to_move.key());
+        assert_ne!(x_to_move.key(), x_to_move.apply((2, 2)).key());
+
+        let too_large = Position {
+            board: Board::with_size(7).unwrap(),
+            to_move: Cell::X,
+        };
+        assert_eq!(too_large.key(), None);
+
+        // Empty boards of different sizes or win lengths are different positions
+        let key_of = |board: Board| {
+            Position {
+                board,
+                to_move: Cell::X,
+            }
+            .key()
+        };
+        assert_ne!(key_of(Board::new()), key_of(Board::with_size(4).unwrap()));
+        assert_ne!(
+            key_of(Board::with_win_length(4, 4).unwrap()),
+            key_of(Board::with_win_length(4, 3).unwrap())
+        );
+        assert!(key_of(Board::with_size(6).unwrap()).is_some());
+    }
+
+    #[test]
+    fn test_reused_agent_keeps_board_settings_apart() {
+        // The same cells: nobody can complete four in a row, but X can make three
+        let grid = "XO.O\nOX..\nX.OO\nOXXX";
+        let four = Board::from_str_grid(grid).unwrap();
+        let mut three = Board::with_win_length(4, 3).unwrap();
+        for (row, col, cell) in four.iter_occupied() {
+            three.set(row, col, cell);
+        }
+
+        let reused = AiAgent::new();
+        let four_score = reused.evaluate(&four, Cell::X);
+        let three_score = reused.evaluate(&three, Cell::X);
+        assert_ne!(four_score, three_score);
+        assert_eq!(three_score, AiAgent::new().evaluate(&three, Cell::X));
+        assert_eq!(four_score, AiAgent::new().evaluate(&four, Cell::X));
+    }
+
+    #[test]
+    fn test_depth_limited_search_blocks_on_4x4() {
+        // X threatens to complete the top row; a full search of this board is far too slow
+        let board = Board::from_str_grid("XXX.\nOO..\n....\n....").unwrap();
+        let ai = AiAgent::with_max_depth(2);
+        assert_eq!(ai.max_depth(), Some(2));
+        assert_eq!(ai.get_best_move(&board), Some((0, 3)));
+
+        // With its own win available, O takes it instead of blocking
+        let board = Board::from_str_grid("XXX.\nOOO.\nX...\n....").unwrap();
+        assert_eq!(ai.get_best_move(&board), Some((1, 3)));
+
+        assert_eq!(AiAgent::new().max_depth(), None);
+    }
+
+    #[test]
+    #[cfg(feature = "std")]
+    fn test_time_limit_returns_a_legal_move_on_large_board() {
+        let mut board = Board::with_size(7).unwrap();
+        board.set(3, 3, Cell::X);
+        let ai = AiAgent::with_time_limit(Duration::from_millis(1));
+        assert_eq!(ai.time_limit(), Some(Duration::from_millis(1)));
+        let (row, col) = ai.get_best_move(&board).unwrap();
+        assert!(board.is_empty(row, col));
+
+        // 3x3 positions are always searched to the end, so the move is the optimal one
+        let board = Board::from_str_grid("X..\n.O.\n..X").unwrap();
+        let ai = AiAgent::with_time_limit(Duration::ZERO);
+        assert_eq!(
+            ai.get_best_move(&board),
+            AiAgent::new().get_best_move(&board)
+        );
+        assert_eq!(AiAgent::new().time_limit(), None);
+    }
+
+    #[test]
+    #[cfg(feature = "std")]
+    fn test_search_stats() {
+        let ai = AiAgent::new();
+        let (best_move, stats) = ai.get_best_move_with_stats(&Board::new());
+        assert_eq!(best_move, Some((1, 1)));
+        // Far more positions than the 9 candidate moves are searched
+        assert!(stats.nodes_visited > 100);
+
+        // Stats cover only the decision they come with
+        let board = Board::from_str_grid("XOX\nXOO\nOX.").unwrap();
+        let (best_move, stats) = ai.get_best_move_with_stats(&board);
+        assert_eq!(best_move, Some((2, 2)));
+        assert_eq!(stats.nodes_visited, 1);
+
+        // Two empty cells and neither move ends the game: each candidate's position, then
+        // the one reply left after it
+        let board = Board::from_str_grid("XOX\nXOO\n.X.").unwrap();
+        let (_, stats) = AiAgent::new().get_best_move_with_stats(&board);
+        assert_eq!(stats.nodes_visited, 4);
+
+        let (best_move, stats) =
+            ai.get_best_move_with_stats(&Board::from_str_grid("XOX\nXOO\nOXX").unwrap());
+        assert_eq!(best_move, None);
+        assert_eq!(stats.nodes_visited, 0);
+    }
+
+    #[test]
+    #[cfg(feature = "std")]
+    fn test_opening_book_skips_search() {
+        let mut ai = AiAgent::new();
+        assert!(!ai.uses_opening_book());
+        ai.set_opening_book(true);
+
+        let mut board = Board::new();
+        assert_eq!(ai.get_best_move_for(&board, Cell::X), Some((1, 1)));
+        board.set(1, 1, Cell::X);
+        assert_eq!(ai.get_best_move(&board), Some((0, 0)));
+        assert_eq!(ai.nodes_visited.get(), 0);
+
+        // Past the book the agent searches as usual
+        board.set(0, 0, Cell::O);
+        assert!(ai.get_best_move_for(&board, Cell::X).is_some());
+        assert!(ai.nodes_visited.get() > 0);
+
+        // Without the book even the first move is searched
+        let plain = AiAgent::new();
+        assert_eq!(plain.get_best_move(&Board::new()), Some((1, 1)));
+        assert!(plain.nodes_visited.get() > 0);
+    }
+
+    #[test]
+    fn test_best_drop_respects_gravity() {
+        let ai = AiAgent::new();
+        // X threatens the bottom row; O must block at its landing cell
+        let board = Board::from_str_grid("...\n...\nXX.").unwrap();
+        assert_eq!(ai.get_best_drop_for(&board, Cell::O), Some((2, 2)));
+
+        // Both sides threaten a column; X moves first and completes the left one
+        let board = Board::from_str_grid("...\nX.O\nXOO").unwrap();
+        assert_eq!(ai.get_best_drop_for(&board, Cell::X), Some((0, 0)));
+
+        let easy = AiAgent::with_difficulty(Difficulty::Easy);
+        for _ in 0..10 {
+            let (row, col) = easy.get_best_drop_for(&board, Cell::X).unwrap();
+            assert_eq!(board.landing_row(col), Some(row));
+        }
+    }
+
+    #[test]
+    fn test_misere_avoids_completing_a_line() {
+        // O can complete the top row
+        let board = Board::from_str_grid("OO.\nXX.\nX..").unwrap();
+        let mut ai = AiAgent::new();
+        assert_eq!(ai.get_best_move(&board), Some((0, 2)));
+
+        ai.set_rules(RuleSet::Misere);
+        assert_eq!(ai.rules(), RuleSet::Misere);
+        let (row, col) = ai.get_best_move(&board).unwrap();
+        let mut after = board.clone();
+        after.set(row, col, Cell::O);
+        assert_ne!(after.check_winner(), Some(Cell::O));
+
+        // Completing a line under misère counts against the mark that did it
+        let mut finished = board.clone();
+        finished.set(0, 2, Cell::O);
+        assert_eq!(ai.evaluate(&finished, Cell::X), 100);
+    }
 }
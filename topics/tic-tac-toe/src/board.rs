@@ -1,18 +1,50 @@
 //! Board module - Game state representation
 
-use std::fmt;
+use crate::record::GameRecord;
+use crate::rng::splitmix64;
+use alloc::string::{String, ToString};
+use alloc::vec::Vec;
+use alloc::{format, vec};
+use core::fmt;
+use core::str::FromStr;
 
-/// Board size constant
-const BOARD_SIZE: usize = 3;
+/// Side length of the classic board created by `Board::new`
+const DEFAULT_SIZE: usize = 3;
+/// Smallest side length accepted by the checked constructors
+const MIN_SIZE: usize = 3;
+
+/// Steps a winning run can take: along a row, down a column, then both diagonals
+const DIRECTIONS: [(usize, isize); 4] = [(0, 1), (1, 0), (1, 1), (1, -1)];
 
 /// Represents a cell on the tic-tac-toe board
-#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub enum Cell {
     Empty,
     X,
     O,
 }
 
+impl Cell {
+    /// Returns the mark playing against this one; `Empty` stays `Empty`
+    pub fn opponent(self) -> Cell {
+        match self {
+            Cell::X => Cell::O,
+            Cell::O => Cell::X,
+            Cell::Empty => Cell::Empty,
+        }
+    }
+
+    /// Returns the digit used for this cell in base-3 board encodings: empty 0, X 1, O 2
+    pub(crate) fn base3_digit(self) -> u8 {
+        match self {
+            Cell::Empty => 0,
+            Cell::X => 1,
+            Cell::O => 2,
+        }
+    }
+}
+
 impl fmt::Display for Cell {
     fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
         match self {
@@ -23,24 +55,289 @@ impl fmt::Display for Cell {
     }
 }
 
-/// Represents the 3x3 tic-tac-toe board
+/// Error returned when text doesn't name a cell
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ParseCellError {
+    /// The text that couldn't be parsed
+    pub input: String,
+}
+
+impl fmt::Display for ParseCellError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "{:?} is not a cell (use X, O, '.', space or nothing for empty)",
+            self.input
+        )
+    }
+}
+
+impl core::error::Error for ParseCellError {}
+
+impl FromStr for Cell {
+    type Err = ParseCellError;
+
+    /// Accepts `X` or `O` in either case, and `.`, a single space or nothing for empty
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "X" | "x" => Ok(Cell::X),
+            "O" | "o" => Ok(Cell::O),
+            "" | " " | "." => Ok(Cell::Empty),
+            _ => Err(ParseCellError {
+                input: s.to_string(),
+            }),
+        }
+    }
+}
+
+/// A board position with named coordinates, so rows and columns can't be swapped by accident
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct Move {
+    pub row: usize,
+    pub col: usize,
+}
+
+impl Move {
+    /// Creates a move at the given row and column
+    pub fn new(row: usize, col: usize) -> Self {
+        Self { row, col }
+    }
+
+    /// Creates a move from a row-major flat index on a board of the given size
+    pub fn from_index(index: usize, size: usize) -> Self {
+        Self {
+            row: index / size,
+            col: index % size,
+        }
+    }
+
+    /// Returns the row-major flat index of the move on a board of the given size
+    pub fn index(&self, size: usize) -> usize {
+        self.row * size + self.col
+    }
+}
+
+impl From<(usize, usize)> for Move {
+    fn from((row, col): (usize, usize)) -> Self {
+        Self { row, col }
+    }
+}
+
+impl From<Move> for (usize, usize) {
+    fn from(mv: Move) -> Self {
+        (mv.row, mv.col)
+    }
+}
+
+/// A winning line that one mark can still complete, as returned by `Board::open_lines_for`
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct LineInfo {
+    /// Positions along the line, in order
+    pub cells: Vec<(usize, usize)>,
+    /// How many of the cells already hold the mark; the rest are empty
+    pub marks: usize,
+}
+
+impl LineInfo {
+    /// Returns the number of empty cells left on the line
+    pub fn empty(&self) -> usize {
+        self.cells.len() - self.marks
+    }
+}
+
+/// Represents errors describing a board that can't occur in a real game
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum BoardError {
+    ImpossibleMarkCounts,
+    /// The board would be smaller than 3x3
+    InvalidSize,
+    /// The win length is zero or longer than the board
+    InvalidWinLength,
+    /// The number of cells doesn't match the board size
+    WrongCellCount,
+}
+
+impl fmt::Display for BoardError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            BoardError::ImpossibleMarkCounts => {
+                write!(f, "Mark counts are impossible with alternating turns")
+            }
+            BoardError::InvalidSize => write!(f, "Board must be at least 3x3"),
+            BoardError::InvalidWinLength => {
+                write!(f, "Win length must be between 1 and the board size")
+            }
+            BoardError::WrongCellCount => write!(f, "Cell count doesn't match the board size"),
+        }
+    }
+}
+
+impl core::error::Error for BoardError {}
+
+/// How `Board::render_styled` draws the grid
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum RenderStyle {
+    /// Plain ASCII pipes and dashes, as `Display` prints
+    #[default]
+    Ascii,
+    /// Box-drawing characters for the grid lines
+    Unicode,
+    /// The ASCII grid with X in red and O in blue, using ANSI escape codes
+    Colored,
+}
+
+/// Whether completing a line wins or loses the game
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub enum RuleSet {
+    /// The first mark to complete a line wins
+    #[default]
+    Standard,
+    /// The first mark to complete a line loses
+    Misere,
+}
+
+/// Represents errors that can occur while parsing a board from a text grid
 #[derive(Debug, Clone, PartialEq, Eq)]
+pub enum ParseBoardError {
+    /// The grid has no rows
+    Empty,
+    /// The number of rows doesn't match the row length
+    WrongRowCount { expected: usize, found: usize },
+    /// A row is longer or shorter than the first row
+    WrongRowLength {
+        row: usize,
+        expected: usize,
+        found: usize,
+    },
+    /// A character that isn't a cell: `X`, `O`, `.` or space
+    InvalidChar { row: usize, col: usize, found: char },
+    /// The grid is smaller than 3x3
+    TooSmall(usize),
+}
+
+impl fmt::Display for ParseBoardError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            ParseBoardError::Empty => write!(f, "Board grid is empty"),
+            ParseBoardError::TooSmall(size) => {
+                write!(
+                    f,
+                    "Board grid is {}x{} but must be at least 3x3",
+                    size, size
+                )
+            }
+            ParseBoardError::WrongRowCount { expected, found } => {
+                write!(f, "Expected {} rows but found {}", expected, found)
+            }
+            ParseBoardError::WrongRowLength {
+                row,
+                expected,
+                found,
+            } => write!(
+                f,
+                "Row {} has {} cells but should have {}",
+                row, found, expected
+            ),
+            ParseBoardError::InvalidChar { row, col, found } => write!(
+                f,
+                "Invalid character {:?} at row {}, column {} (use X, O, '.' or space)",
+                found, row, col
+            ),
+        }
+    }
+}
+
+impl core::error::Error for ParseBoardError {}
+
+/// Represents a square tic-tac-toe board, 3x3 unless created with `Board::with_size`
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(feature = "serde", serde(try_from = "BoardFields"))]
 pub struct Board {
-    cells: [[Cell; BOARD_SIZE]; BOARD_SIZE],
+    size: usize,
+    /// Number of marks in a row needed to win
+    win_length: usize,
+    /// Cells in reading order, indexed by `row * size + col`
+    cells: Vec<Cell>,
+}
+
+/// The fields of a deserialized board, checked against each other before becoming a `Board`
+#[cfg(feature = "serde")]
+#[derive(serde::Deserialize)]
+struct BoardFields {
+    size: usize,
+    win_length: usize,
+    cells: Vec<Cell>,
+}
+
+#[cfg(feature = "serde")]
+impl TryFrom<BoardFields> for Board {
+    type Error = BoardError;
+
+    fn try_from(fields: BoardFields) -> Result<Self, Self::Error> {
+        let mut board = Board::with_win_length(fields.size, fields.win_length)?;
+        if fields.cells.len() != board.cells.len() {
+            return Err(BoardError::WrongCellCount);
+        }
+        board.cells = fields.cells;
+        Ok(board)
+    }
 }
 
 impl Board {
-    /// Creates a new empty board
+    /// Creates a new empty 3x3 board
     pub fn new() -> Self {
+        Self::blank(DEFAULT_SIZE, DEFAULT_SIZE)
+    }
+
+    /// Creates a new empty board with `size` rows and columns, won by filling a whole line
+    /// Returns `BoardError::InvalidSize` for boards smaller than 3x3
+    pub fn with_size(size: usize) -> Result<Self, BoardError> {
+        Self::with_win_length(size, size)
+    }
+
+    /// Creates a new empty board with `size` rows and columns, won by `win_length` in a row
+    /// Returns `BoardError::InvalidSize` for boards smaller than 3x3, and
+    /// `BoardError::InvalidWinLength` if `win_length` is zero or longer than the board
+    pub fn with_win_length(size: usize, win_length: usize) -> Result<Self, BoardError> {
+        if size < MIN_SIZE {
+            Err(BoardError::InvalidSize)
+        } else if !(1..=size).contains(&win_length) {
+            Err(BoardError::InvalidWinLength)
+        } else {
+            Ok(Self::blank(size, win_length))
+        }
+    }
+
+    /// Creates an empty board without checking its settings
+    fn blank(size: usize, win_length: usize) -> Self {
         Self {
-            cells: [[Cell::Empty; BOARD_SIZE]; BOARD_SIZE],
+            size,
+            win_length,
+            cells: vec![Cell::Empty; size * size],
         }
     }
 
+    /// Returns an empty board with the same size and win length
+    pub(crate) fn cleared(&self) -> Board {
+        Self::blank(self.size, self.win_length)
+    }
+
+    /// Returns the number of rows (and columns) on the board
+    pub fn size(&self) -> usize {
+        self.size
+    }
+
+    /// Returns the number of marks in a row needed to win
+    pub fn win_length(&self) -> usize {
+        self.win_length
+    }
+
     /// Gets the cell at the specified position
     pub fn get(&self, row: usize, col: usize) -> Option<Cell> {
-        if row < BOARD_SIZE && col < BOARD_SIZE {
-            Some(self.cells[row][col])
+        if row < self.size && col < self.size {
+            Some(self.cell(row, col))
         } else {
             None
         }
@@ -49,14 +346,135 @@ impl Board {
     /// Sets the cell at the specified position
     /// Returns true if the move was valid (cell was empty), false otherwise
     pub fn set(&mut self, row: usize, col: usize, cell: Cell) -> bool {
-        if row < BOARD_SIZE && col < BOARD_SIZE && self.cells[row][col] == Cell::Empty {
-            self.cells[row][col] = cell;
+        if self.is_empty(row, col) {
+            self.cells[row * self.size + col] = cell;
             true
         } else {
             false
         }
     }
 
+    /// Parses a square grid with one line per row, reading each character as a `Cell`,
+    /// e.g. `"XOX\n.O.\nX.O"`. The row length sets the board size
+    pub fn from_str_grid(s: &str) -> Result<Board, ParseBoardError> {
+        let rows: Vec<Vec<char>> = s
+            .lines()
+            .map(|line| line.trim_end_matches('\r').chars().collect())
+            .collect();
+        let size = rows.first().ok_or(ParseBoardError::Empty)?.len();
+        if size == 0 {
+            return Err(ParseBoardError::Empty);
+        }
+        if rows.len() != size {
+            return Err(ParseBoardError::WrongRowCount {
+                expected: size,
+                found: rows.len(),
+            });
+        }
+
+        let mut board = Board::with_size(size).map_err(|_| ParseBoardError::TooSmall(size))?;
+        for (row, chars) in rows.iter().enumerate() {
+            if chars.len() != size {
+                return Err(ParseBoardError::WrongRowLength {
+                    row,
+                    expected: size,
+                    found: chars.len(),
+                });
+            }
+
+            for (col, &found) in chars.iter().enumerate() {
+                let cell = found
+                    .to_string()
+                    .parse()
+                    .map_err(|_| ParseBoardError::InvalidChar { row, col, found })?;
+                board.cells[row * size + col] = cell;
+            }
+        }
+        Ok(board)
+    }
+
+    /// Writes the board in the format read by `from_str_grid`, using `.` for empty cells
+    pub fn to_str_grid(&self) -> String {
+        let rows: Vec<String> = (0..self.size)
+            .map(|row| {
+                (0..self.size)
+                    .map(|col| match self.cell(row, col) {
+                        Cell::X => 'X',
+                        Cell::O => 'O',
+                        Cell::Empty => '.',
+                    })
+                    .collect()
+            })
+            .collect();
+        rows.join("\n")
+    }
+
+    /// Returns the cell at a position known to be on the board
+    fn cell(&self, row: usize, col: usize) -> Cell {
+        self.cells[row * self.size + col]
+    }
+
+    /// Yields every cell with its row and column, in reading order
+    pub fn iter_cells(&self) -> impl Iterator<Item = (usize, usize, Cell)> + '_ {
+        self.positions()
+            .map(|(row, col)| (row, col, self.cell(row, col)))
+    }
+
+    /// Yields every cell holding a mark with its row and column, in reading order
+    pub fn iter_occupied(&self) -> impl Iterator<Item = (usize, usize, Cell)> + '_ {
+        self.iter_cells()
+            .filter(|&(_, _, cell)| cell != Cell::Empty)
+    }
+
+    /// Returns every position on the board in reading order
+    fn positions(&self) -> impl Iterator<Item = (usize, usize)> {
+        let size = self.size;
+        (0..size * size).map(move |index| (index / size, index % size))
+    }
+
+    /// Returns every run of `win_length` cells that wins the game:
+    /// along rows, down columns, then both diagonals
+    pub fn lines(&self) -> Vec<Vec<(usize, usize)>> {
+        DIRECTIONS
+            .iter()
+            .flat_map(|&step| {
+                self.run_starts(step).map(move |(row, col)| {
+                    (0..self.win_length)
+                        .map(|i| Self::step_from((row, col), step, i))
+                        .collect()
+                })
+            })
+            .collect()
+    }
+
+    /// Returns the positions where a run in direction `step` can start and stay on the board
+    fn run_starts(&self, (d_row, d_col): (usize, isize)) -> impl Iterator<Item = (usize, usize)> {
+        let span = self.win_length - 1;
+        let rows = 0..self.size - d_row * span;
+        let cols = match d_col {
+            1 => 0..self.size - span,
+            -1 => span..self.size,
+            _ => 0..self.size,
+        };
+        rows.flat_map(move |row| cols.clone().map(move |col| (row, col)))
+    }
+
+    /// Returns the position `i` steps from `start` in direction `step`
+    fn step_from(
+        (row, col): (usize, usize),
+        (d_row, d_col): (usize, isize),
+        i: usize,
+    ) -> (usize, usize) {
+        (row + d_row * i, col.wrapping_add_signed(d_col * i as isize))
+    }
+
+    /// Sets the cell at the position described by `mv`, accepting a `Move` or a `(row, col)` tuple
+    /// Returns true if the move was valid (cell was empty), false otherwise
+    pub fn set_at(&mut self, mv: impl Into<Move>, cell: Cell) -> bool {
+        let mv = mv.into();
+        self.set(mv.row, mv.col, cell)
+    }
+
     /// Checks if the specified position is empty
     pub fn is_empty(&self, row: usize, col: usize) -> bool {
         self.get(row, col) == Some(Cell::Empty)
@@ -64,199 +482,762 @@ impl Board {
 
     /// Returns true if the board is full
     pub fn is_full(&self) -> bool {
-        for row in 0..BOARD_SIZE {
-            for col in 0..BOARD_SIZE {
-                if self.cells[row][col] == Cell::Empty {
-                    return false;
-                }
-            }
-        }
-        true
+        !self.cells.contains(&Cell::Empty)
     }
 
     /// Gets all empty positions on the board
     pub fn empty_positions(&self) -> Vec<(usize, usize)> {
-        let mut positions = Vec::new();
-        for row in 0..BOARD_SIZE {
-            for col in 0..BOARD_SIZE {
-                if self.cells[row][col] == Cell::Empty {
-                    positions.push((row, col));
-                }
-            }
+        self.positions()
+            .filter(|&(row, col)| self.cell(row, col) == Cell::Empty)
+            .collect()
+    }
+
+    /// Returns the lowest empty row in `col`, where a dropped mark comes to rest,
+    /// or None if the column is full or off the board
+    pub fn landing_row(&self, col: usize) -> Option<usize> {
+        if col >= self.size {
+            return None;
         }
-        positions
+        (0..self.size)
+            .rev()
+            .find(|&row| self.cell(row, col) == Cell::Empty)
+    }
+
+    /// Drops `cell` into `col` so it falls to the lowest empty row, and returns that row
+    /// Returns None, leaving the board unchanged, if the column is full or off the board
+    pub fn drop(&mut self, col: usize, cell: Cell) -> Option<usize> {
+        let row = self.landing_row(col)?;
+        self.set(row, col, cell);
+        Some(row)
+    }
+
+    /// Gets the position a dropped mark would land on in every column that isn't full
+    pub fn landing_positions(&self) -> Vec<(usize, usize)> {
+        (0..self.size)
+            .filter_map(|col| Some((self.landing_row(col)?, col)))
+            .collect()
+    }
+
+    /// Returns the number of marks on the board, i.e. how many plies
+    /// have been played since the empty board
+    pub fn plies_from_start(&self) -> usize {
+        let (x_count, o_count) = self.mark_counts();
+        x_count + o_count
+    }
+
+    /// Returns the number of empty cells, i.e. the most plies that can still be played
+    pub fn remaining_plies(&self) -> usize {
+        self.empty_count()
+    }
+
+    /// Returns the number of empty cells without collecting their positions
+    pub fn empty_count(&self) -> usize {
+        self.count(Cell::Empty)
+    }
+
+    /// Returns the first empty position in reading order without allocating
+    pub fn first_empty(&self) -> Option<(usize, usize)> {
+        self.positions()
+            .find(|&(row, col)| self.cell(row, col) == Cell::Empty)
     }
 
     /// Checks if there's a winner and returns the winning cell type
     pub fn check_winner(&self) -> Option<Cell> {
-        // Check rows
-        for row in 0..BOARD_SIZE {
-            if self.cells[row][0] != Cell::Empty
-                && self.cells[row][0] == self.cells[row][1]
-                && self.cells[row][1] == self.cells[row][2]
-            {
-                return Some(self.cells[row][0]);
-            }
-        }
+        self.lines().iter().find_map(|line| self.line_owner(line))
+    }
 
-        // Check columns
-        for col in 0..BOARD_SIZE {
-            if self.cells[0][col] != Cell::Empty
-                && self.cells[0][col] == self.cells[1][col]
-                && self.cells[1][col] == self.cells[2][col]
-            {
-                return Some(self.cells[0][col]);
-            }
-        }
+    /// Returns the mark at `last` if it lies on a completed line, looking only at the
+    /// `win_length - 1` cells on each side of it in every direction
+    /// Runs longer than `win_length` count too. Meant for checking after each move on
+    /// large boards, where `check_winner` would scan the whole grid
+    pub fn check_winner_near(&self, (row, col): (usize, usize)) -> Option<Cell> {
+        let mark = self.get(row, col).filter(|&cell| cell != Cell::Empty)?;
+        let completes_line = |&(d_row, d_col): &(usize, isize)| {
+            let holds_mark = |i: isize| {
+                let neighbor = (
+                    row.checked_add_signed(d_row as isize * i),
+                    col.checked_add_signed(d_col * i),
+                );
+                matches!(neighbor, (Some(r), Some(c)) if self.get(r, c) == Some(mark))
+            };
+            let run = |sign: isize| {
+                (1..self.win_length as isize)
+                    .take_while(|&i| holds_mark(i * sign))
+                    .count()
+            };
+            1 + run(1) + run(-1) >= self.win_length
+        };
+        DIRECTIONS.iter().any(completes_line).then_some(mark)
+    }
 
-        // Check main diagonal (top-left to bottom-right)
-        if self.cells[0][0] != Cell::Empty
-            && self.cells[0][0] == self.cells[1][1]
-            && self.cells[1][1] == self.cells[2][2]
-        {
-            return Some(self.cells[0][0]);
+    /// Returns the winner along with the cells of the first completed line,
+    /// checking rows, columns, then both diagonals
+    pub fn winning_line(&self) -> Option<(Cell, Vec<(usize, usize)>)> {
+        // Walks each run in place rather than through `lines`, since the solver
+        // calls this at every node
+        DIRECTIONS.iter().find_map(|&step| {
+            self.run_starts(step).find_map(|start| {
+                let winner = self.run_owner(start, step)?;
+                let line = (0..self.win_length)
+                    .map(|i| Self::step_from(start, step, i))
+                    .collect();
+                Some((winner, line))
+            })
+        })
+    }
+
+    /// Returns the mark filling the `win_length` cells from `start` in direction `step`, if any
+    fn run_owner(&self, start: (usize, usize), step: (usize, isize)) -> Option<Cell> {
+        let first = self.cell(start.0, start.1);
+        if first == Cell::Empty {
+            return None;
         }
 
-        // Check anti-diagonal (top-right to bottom-left)
-        if self.cells[0][2] != Cell::Empty
-            && self.cells[0][2] == self.cells[1][1]
-            && self.cells[1][1] == self.cells[2][0]
-        {
-            return Some(self.cells[0][2]);
+        (1..self.win_length)
+            .all(|i| {
+                let (row, col) = Self::step_from(start, step, i);
+                self.cell(row, col) == first
+            })
+            .then_some(first)
+    }
+
+    /// Returns the mark filling every cell of `line`, if any
+    fn line_owner(&self, line: &[(usize, usize)]) -> Option<Cell> {
+        let (first_row, first_col) = *line.first()?;
+        let first = self.cell(first_row, first_col);
+        let uniform = line.iter().all(|&(row, col)| self.cell(row, col) == first);
+        if first != Cell::Empty && uniform {
+            Some(first)
+        } else {
+            None
         }
+    }
 
-        None
+    /// Returns every completed line along with the cell type that completed it
+    /// Unlike `check_winner`, this reports all lines, so boards showing both
+    /// players winning (only possible when constructed by hand) can be detected
+    pub fn all_winners(&self) -> Vec<(Cell, Vec<(usize, usize)>)> {
+        self.lines()
+            .into_iter()
+            .filter_map(|line| self.line_owner(&line).map(|owner| (owner, line)))
+            .collect()
     }
 
-    /// Returns true if the game is over (either someone won or board is full)
-    pub fn is_game_over(&self) -> bool {
-        self.check_winner().is_some() || self.is_full()
+    /// Returns a bitmask for every winning line of the 3x3 board, where bit `row * 3 + col`
+    /// marks a cell. A mark has completed a line when `mask & board.occupancy(mark) == mask`
+    pub fn winning_masks() -> Vec<u16> {
+        Board::new()
+            .lines()
+            .iter()
+            .map(|line| {
+                line.iter().fold(0, |mask, &(row, col)| {
+                    mask | 1 << (row * DEFAULT_SIZE + col)
+                })
+            })
+            .collect()
     }
-}
 
-impl Default for Board {
-    fn default() -> Self {
-        Self::new()
+    /// Returns a bitmask of the cells holding `cell`, using the same indexing as `winning_masks`
+    /// Only the first 16 cells fit, so this is meant for boards up to 4x4
+    pub fn occupancy(&self, cell: Cell) -> u16 {
+        self.cells
+            .iter()
+            .take(u16::BITS as usize)
+            .enumerate()
+            .filter(|&(_, &occupant)| occupant == cell)
+            .fold(0, |mask, (index, _)| mask | 1 << index)
     }
-}
 
-impl fmt::Display for Board {
-    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
-        writeln!(f, "  0   1   2")?;
-        for row in 0..BOARD_SIZE {
-            write!(f, "{} ", row)?;
-            for col in 0..BOARD_SIZE {
-                write!(f, "{}", self.cells[row][col])?;
-                if col < BOARD_SIZE - 1 {
-                    write!(f, " | ")?;
-                }
-            }
-            writeln!(f)?;
-            if row < BOARD_SIZE - 1 {
-                writeln!(f, "  ---------")?;
-            }
+    /// Packs the cells of a 3x3 board, in reading order, into base-3 digits
+    /// (empty 0, X 1, O 2) with the first cell most significant
+    /// Only the first 9 cells are encoded, so this is meant for the standard board
+    pub fn encode(&self) -> u32 {
+        self.cells
+            .iter()
+            .take(DEFAULT_SIZE * DEFAULT_SIZE)
+            .fold(0, |code, cell| code * 3 + u32::from(cell.base3_digit()))
+    }
+
+    /// Rebuilds the 3x3 board packed by `encode`
+    /// Digits above the 9 cells are ignored
+    pub fn decode(code: u32) -> Board {
+        let mut board = Board::new();
+        let mut code = code;
+        for cell in board.cells.iter_mut().rev() {
+            *cell = match code % 3 {
+                0 => Cell::Empty,
+                1 => Cell::X,
+                _ => Cell::O,
+            };
+            code /= 3;
         }
-        Ok(())
+        board
     }
-}
 
-#[cfg(test)]
-mod tests {
-    use super::*;
+    /// Counts the winning lines that hold at least one `mark` and no opposing marks
+    /// These are the lines `mark` can still complete
+    pub fn open_line_count(&self, mark: Cell) -> usize {
+        self.open_lines_for(mark).len()
+    }
 
-    #[test]
-    fn test_new_board() {
-        let board = Board::new();
-        assert!(board.is_empty(0, 0));
-        assert!(board.is_empty(2, 2));
-        assert!(!board.is_full());
-        assert!(board.check_winner().is_none());
+    /// Returns the winning lines that hold at least one `mark` and no opposing marks
+    /// Lines one mark short of complete are immediate threats
+    pub fn open_lines_for(&self, mark: Cell) -> Vec<LineInfo> {
+        self.lines()
+            .into_iter()
+            .filter_map(|cells| {
+                let mut marks = 0;
+                for &(row, col) in &cells {
+                    match self.cell(row, col) {
+                        Cell::Empty => {}
+                        cell if cell == mark => marks += 1,
+                        _ => return None,
+                    }
+                }
+                (marks > 0).then_some(LineInfo { cells, marks })
+            })
+            .collect()
     }
 
-    #[test]
-    fn test_set_and_get() {
-        let mut board = Board::new();
-        assert!(board.set(1, 1, Cell::X));
-        assert_eq!(board.get(1, 1), Some(Cell::X));
-        assert!(!board.set(1, 1, Cell::O)); // Can't overwrite
+    /// Returns true if some winning line holds marks of at most one side, so it could still
+    /// be completed; false means the game can only end in a draw, even with cells left empty
+    pub fn has_winning_possibility(&self) -> bool {
+        self.lines().iter().any(|line| {
+            let holds = |mark| line.iter().any(|&(row, col)| self.cell(row, col) == mark);
+            !(holds(Cell::X) && holds(Cell::O))
+        })
     }
 
-    #[test]
-    fn test_winner_detection() {
-        let mut board = Board::new();
+    /// Returns every empty position where `mark` would immediately complete a line
+    /// Two or more at once are a double threat (fork) the opponent can't fully block
+    pub fn winning_moves_for(&self, mark: Cell) -> Vec<(usize, usize)> {
+        self.empty_positions()
+            .into_iter()
+            .filter(|&(row, col)| self.would_win(row, col, mark))
+            .collect()
+    }
 
-        // Set up a winning row
-        board.set(0, 0, Cell::X);
-        board.set(0, 1, Cell::X);
-        board.set(0, 2, Cell::X);
+    /// Returns true if placing `cell` at `(row, col)` would complete a line for it
+    /// Returns false for occupied or off-board positions; the board itself is unchanged
+    pub fn would_win(&self, row: usize, col: usize, cell: Cell) -> bool {
+        let mut board_copy = self.clone();
+        board_copy.set(row, col, cell) && board_copy.check_winner_near((row, col)) == Some(cell)
+    }
 
-        assert_eq!(board.check_winner(), Some(Cell::X));
+    /// Returns true if playing `mark` at `mv` leaves it two or more immediate wins
+    /// Returns false if `mv` is off the board or occupied
+    pub fn creates_fork(&self, mark: Cell, (row, col): (usize, usize)) -> bool {
+        let mut board_copy = self.clone();
+        board_copy.set(row, col, mark) && board_copy.winning_moves_for(mark).len() >= 2
     }
 
-    #[test]
-    fn test_all_winning_conditions() {
-        // Test all 8 possible winning combinations
+    /// Scores an unfinished board for `ai`: the lines `ai` can still complete minus
+    /// the lines its opponent can still complete
+    pub fn heuristic_score(&self, ai: Cell) -> i32 {
+        if ai == Cell::Empty {
+            return 0;
+        }
+        self.open_line_count(ai) as i32 - self.open_line_count(ai.opponent()) as i32
+    }
 
-        // Test all rows
-        for row in 0..BOARD_SIZE {
-            let mut board = Board::new();
-            for col in 0..BOARD_SIZE {
-                board.set(row, col, Cell::O);
+    /// Returns, for every empty cell, how many lines through it `cell` could still complete
+    /// Occupied cells are zero
+    pub fn influence(&self, cell: Cell) -> Vec<Vec<i32>> {
+        let mut map = vec![vec![0; self.size]; self.size];
+        for line in self.lines() {
+            let winnable = line.iter().all(|&(row, col)| {
+                let occupant = self.cell(row, col);
+                occupant == Cell::Empty || occupant == cell
+            });
+            if !winnable {
+                continue;
             }
-            assert_eq!(
-                board.check_winner(),
-                Some(Cell::O),
-                "Row {} should be a win",
-                row
-            );
-        }
 
-        // Test all columns
-        for col in 0..BOARD_SIZE {
-            let mut board = Board::new();
-            for row in 0..BOARD_SIZE {
-                board.set(row, col, Cell::X);
+            for &(row, col) in line.iter() {
+                if self.cell(row, col) == Cell::Empty {
+                    map[row][col] += 1;
+                }
             }
-            assert_eq!(
-                board.check_winner(),
-                Some(Cell::X),
-                "Column {} should be a win",
-                col
-            );
         }
+        map
+    }
 
-        // Test main diagonal (top-left to bottom-right)
-        let mut board = Board::new();
-        for i in 0..BOARD_SIZE {
-            board.set(i, i, Cell::O);
-        }
-        assert_eq!(
-            board.check_winner(),
-            Some(Cell::O),
-            "Main diagonal should be a win"
-        );
+    /// Returns whose turn it is, given which mark moved first
+    /// Errors if the mark counts can't result from alternating turns
+    pub fn side_to_move(&self, x_first: bool) -> Result<Cell, BoardError> {
+        let (x_count, o_count) = self.mark_counts();
+        let (first, second, first_count, second_count) = if x_first {
+            (Cell::X, Cell::O, x_count, o_count)
+        } else {
+            (Cell::O, Cell::X, o_count, x_count)
+        };
 
-        // Test anti-diagonal (top-right to bottom-left)
-        let mut board = Board::new();
-        for i in 0..BOARD_SIZE {
-            board.set(i, BOARD_SIZE - 1 - i, Cell::X);
+        if first_count == second_count {
+            Ok(first)
+        } else if first_count == second_count + 1 {
+            Ok(second)
+        } else {
+            Err(BoardError::ImpossibleMarkCounts)
         }
-        assert_eq!(
-            board.check_winner(),
-            Some(Cell::X),
-            "Anti-diagonal should be a win"
-        );
     }
 
-    #[test]
-    fn test_draw_detection() {
-        let mut board = Board::new();
+    /// Infers whose turn it is from the mark counts, assuming X moved first
+    /// Returns None if the counts can't result from alternating turns
+    pub fn next_to_move(&self) -> Option<Cell> {
+        self.side_to_move(true).ok()
+    }
 
-        // Create a draw scenario: X O X / O X O / O X O
-        board.set(0, 0, Cell::X);
-        board.set(0, 1, Cell::O);
-        board.set(0, 2, Cell::X);
-        board.set(1, 0, Cell::O);
+    /// Returns how many cells hold `cell`
+    pub fn count(&self, cell: Cell) -> usize {
+        self.cells
+            .iter()
+            .filter(|&&occupant| occupant == cell)
+            .count()
+    }
+
+    /// Returns the number of X and O marks on the board
+    fn mark_counts(&self) -> (usize, usize) {
+        (self.count(Cell::X), self.count(Cell::O))
+    }
+
+    /// Returns true if the position could arise from alternating play:
+    /// the mark counts differ by at most one and at most one player has a completed line
+    pub fn is_legal_position(&self) -> bool {
+        let (x_count, o_count) = self.mark_counts();
+        if x_count.abs_diff(o_count) > 1 {
+            return false;
+        }
+
+        let winners = self.all_winners();
+        let x_won = winners.iter().any(|(cell, _)| *cell == Cell::X);
+        let o_won = winners.iter().any(|(cell, _)| *cell == Cell::O);
+        !(x_won && o_won)
+    }
+
+    /// Same check as `is_legal_position`: the mark counts differ by at most one
+    /// and the players haven't both completed a line
+    pub fn is_valid_state(&self) -> bool {
+        self.is_legal_position()
+    }
+
+    /// Returns the single move that turns this board into `next`
+    /// Returns None if the boards are identical, differ in more than one cell,
+    /// or the difference is anything other than a mark placed on an empty cell
+    pub fn is_successor(&self, next: &Board) -> Option<(usize, usize, Cell)> {
+        let mut placed = None;
+
+        if self.size != next.size {
+            return None;
+        }
+
+        for (row, col) in self.positions() {
+            let before = self.cell(row, col);
+            let after = next.cell(row, col);
+            if before == after {
+                continue;
+            }
+
+            if before != Cell::Empty || placed.is_some() {
+                return None;
+            }
+            placed = Some((row, col, after));
+        }
+
+        placed
+    }
+
+    /// Returns every position whose cell differs between the two boards
+    /// Each entry holds the row, column, this board's cell, and the other board's cell
+    /// Both boards are expected to have the same size; positions off `other` are skipped
+    pub fn diff(&self, other: &Board) -> Vec<(usize, usize, Cell, Cell)> {
+        self.positions()
+            .filter_map(|(row, col)| {
+                let before = self.cell(row, col);
+                let after = other.get(row, col)?;
+                (before != after).then_some((row, col, before, after))
+            })
+            .collect()
+    }
+
+    /// Renders this board and `other` side by side, bracketing every cell that differs
+    pub fn diff_display(&self, other: &Board) -> String {
+        let changed: Vec<(usize, usize)> = self
+            .diff(other)
+            .into_iter()
+            .map(|(row, col, _, _)| (row, col))
+            .collect();
+
+        let mut output = String::new();
+        for (left, right) in self
+            .diff_lines(&changed)
+            .into_iter()
+            .zip(other.diff_lines(&changed))
+        {
+            output.push_str(&left);
+            output.push_str("    ");
+            output.push_str(&right);
+            output.push('\n');
+        }
+        output
+    }
+
+    /// Renders the board one line at a time with each cell padded to equal width,
+    /// bracketing the cells listed in `changed`
+    fn diff_lines(&self, changed: &[(usize, usize)]) -> Vec<String> {
+        let labels: Vec<String> = (0..self.size).map(|col| format!(" {} ", col)).collect();
+        let separator = vec!["---"; self.size].join("+");
+        let mut lines = vec![format!("  {}", labels.join(" "))];
+        for row in 0..self.size {
+            let cells: Vec<String> = (0..self.size)
+                .map(|col| {
+                    if changed.contains(&(row, col)) {
+                        format!("[{}]", self.cell(row, col))
+                    } else {
+                        format!(" {} ", self.cell(row, col))
+                    }
+                })
+                .collect();
+            lines.push(format!("{} {}", row, cells.join("|")));
+            if row < self.size - 1 {
+                lines.push(format!("  {}", separator));
+            }
+        }
+        lines
+    }
+
+    /// Returns every legal game prefix of exactly `n` plies, starting with X if `x_first`
+    /// Games that end before reaching `n` plies are left out
+    pub fn games_of_length(n: usize, x_first: bool) -> Vec<GameRecord> {
+        let first = if x_first { Cell::X } else { Cell::O };
+        let mut games = Vec::new();
+        Self::extend_games(&Board::new(), first, n, &mut GameRecord::new(), &mut games);
+        games
+    }
+
+    /// Depth-first helper for `games_of_length`
+    fn extend_games(
+        board: &Board,
+        mark: Cell,
+        remaining: usize,
+        record: &mut GameRecord,
+        games: &mut Vec<GameRecord>,
+    ) {
+        if remaining == 0 {
+            games.push(record.clone());
+            return;
+        }
+        if board.is_game_over() {
+            return;
+        }
+
+        let next = mark.opponent();
+        for (row, col) in board.empty_positions() {
+            let mut board_copy = board.clone();
+            board_copy.set(row, col, mark);
+            record.push(row, col, mark);
+            Self::extend_games(&board_copy, next, remaining - 1, record, games);
+            record.pop();
+        }
+    }
+
+    /// Returns the Zobrist hash of the board: the XOR of a fixed random key per occupied cell
+    /// Placing or removing a mark changes the hash by XOR-ing a single key
+    pub fn zobrist_hash(&self) -> u64 {
+        self.positions().fold(0, |hash, (row, col)| {
+            hash ^ self.zobrist_key(row, col, self.cell(row, col))
+        })
+    }
+
+    /// Returns the Zobrist hash the board would have if the given position were empty
+    pub fn hash_without(&self, row: usize, col: usize) -> u64 {
+        match self.get(row, col) {
+            Some(cell) => self.zobrist_hash() ^ self.zobrist_key(row, col, cell),
+            None => self.zobrist_hash(),
+        }
+    }
+
+    /// Returns the Zobrist key for `cell` at the given position, or 0 for empty cells
+    fn zobrist_key(&self, row: usize, col: usize, cell: Cell) -> u64 {
+        let piece = match cell {
+            Cell::Empty => return 0,
+            Cell::X => 0,
+            Cell::O => 1,
+        };
+
+        // Hashing the (position, piece) pair gives well-spread fixed keys
+        splitmix64(((row * self.size + col) * 2 + piece) as u64)
+    }
+
+    /// Returns a copy of the board with every X replaced by O and vice versa
+    pub fn swap_players(&self) -> Board {
+        let mut swapped = self.clone();
+        for cell in swapped.cells.iter_mut() {
+            *cell = cell.opponent();
+        }
+        swapped
+    }
+
+    /// Returns the board under each of its 8 symmetries: the 4 rotations (starting with
+    /// the board itself), then each of those mirrored left to right
+    /// Symmetric boards repeat some entries
+    pub fn symmetries(&self) -> Vec<Board> {
+        (0..Self::SYMMETRY_COUNT)
+            .map(|symmetry| self.transformed(symmetry))
+            .collect()
+    }
+
+    /// Returns the board turned a quarter turn clockwise
+    pub fn rotated_cw(&self) -> Board {
+        self.transformed(1)
+    }
+
+    /// Returns the board turned a quarter turn counterclockwise
+    pub fn rotated_ccw(&self) -> Board {
+        self.transformed(3)
+    }
+
+    /// Returns the board mirrored left to right
+    pub fn flipped_horizontal(&self) -> Board {
+        self.transformed(4)
+    }
+
+    /// Returns the board mirrored top to bottom
+    pub fn flipped_vertical(&self) -> Board {
+        // A half turn followed by a left-right mirror
+        self.transformed(6)
+    }
+
+    /// Returns true if `other` is this board turned and/or mirrored, i.e. the same position
+    /// up to symmetry
+    pub fn is_symmetric_to(&self, other: &Board) -> bool {
+        if self.size != other.size {
+            return false;
+        }
+
+        let mut turned = self.clone();
+        for _ in 0..4 {
+            if turned == *other || turned.flipped_horizontal() == *other {
+                return true;
+            }
+            turned = turned.rotated_cw();
+        }
+        false
+    }
+
+    /// Number of symmetries of a square: 4 rotations, each optionally mirrored
+    pub(crate) const SYMMETRY_COUNT: usize = 8;
+
+    /// Returns where `symmetry` (numbered as in `symmetries`) sends the given position
+    pub(crate) fn transform_position(
+        &self,
+        symmetry: usize,
+        (row, col): (usize, usize),
+    ) -> (usize, usize) {
+        let last = self.size - 1;
+        let (mut row, mut col) = (row, col);
+        // Each quarter turn clockwise sends (row, col) to (col, last - row)
+        for _ in 0..symmetry % 4 {
+            (row, col) = (col, last - row);
+        }
+        if symmetry >= 4 {
+            col = last - col;
+        }
+        (row, col)
+    }
+
+    /// Returns the board with every cell moved by `symmetry`
+    pub(crate) fn transformed(&self, symmetry: usize) -> Board {
+        let mut board = self.clone();
+        for (row, col, cell) in self.iter_cells() {
+            let (new_row, new_col) = self.transform_position(symmetry, (row, col));
+            board.cells[new_row * self.size + new_col] = cell;
+        }
+        board
+    }
+
+    /// Returns true if the game is over (either someone won or board is full)
+    pub fn is_game_over(&self) -> bool {
+        self.winning_line().is_some() || self.is_full()
+    }
+}
+
+impl Board {
+    /// Renders the board as text, exactly as `Display` prints it
+    pub fn render(&self) -> String {
+        self.render_with_origin(false)
+    }
+
+    /// Renders the board like `Display`, labeling rows and columns from 1 when `one_based` is set
+    pub fn render_with_origin(&self, one_based: bool) -> String {
+        let origin = if one_based { 1 } else { 0 };
+        self.render_grid(origin, RenderStyle::Ascii)
+    }
+
+    /// Renders the board in the given style; `RenderStyle::Ascii` matches `render`
+    pub fn render_styled(&self, style: RenderStyle) -> String {
+        self.render_grid(0, style)
+    }
+
+    /// Renders the grid with labels starting at `origin`
+    fn render_grid(&self, origin: usize, style: RenderStyle) -> String {
+        let mut output = String::new();
+        // Writing into a String can't fail
+        let _ = self.write_grid(&mut output, origin, style);
+        output
+    }
+
+    /// Writes the grid with row and column labels starting at `origin`
+    fn write_grid(
+        &self,
+        f: &mut impl fmt::Write,
+        origin: usize,
+        style: RenderStyle,
+    ) -> fmt::Result {
+        let labels: Vec<String> = (0..self.size)
+            .map(|col| (col + origin).to_string())
+            .collect();
+        let (divider, separator) = match style {
+            RenderStyle::Unicode => {
+                // Crossings line up under the dividers, which sit every 4 columns from column 2
+                let separator: String = (0..4 * self.size - 3)
+                    .map(|i| if i % 4 == 2 { '┼' } else { '─' })
+                    .collect();
+                (" │ ", separator)
+            }
+            RenderStyle::Ascii | RenderStyle::Colored => (" | ", "-".repeat(4 * self.size - 3)),
+        };
+        writeln!(f, "  {}", labels.join("   "))?;
+        for row in 0..self.size {
+            write!(f, "{} ", row + origin)?;
+            for col in 0..self.size {
+                let cell = self.cell(row, col);
+                match (style, cell) {
+                    (RenderStyle::Colored, Cell::X) => write!(f, "\x1b[31m{}\x1b[0m", cell)?,
+                    (RenderStyle::Colored, Cell::O) => write!(f, "\x1b[34m{}\x1b[0m", cell)?,
+                    _ => write!(f, "{}", cell)?,
+                }
+                if col < self.size - 1 {
+                    write!(f, "{}", divider)?;
+                }
+            }
+            writeln!(f)?;
+            if row < self.size - 1 {
+                writeln!(f, "  {}", separator)?;
+            }
+        }
+        Ok(())
+    }
+}
+
+impl Default for Board {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl fmt::Display for Board {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}", self.render())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_new_board() {
+        let board = Board::new();
+        assert!(board.is_empty(0, 0));
+        assert!(board.is_empty(2, 2));
+        assert!(!board.is_full());
+        assert!(board.check_winner().is_none());
+    }
+
+    #[test]
+    fn test_set_and_get() {
+        let mut board = Board::new();
+        assert!(board.set(1, 1, Cell::X));
+        assert_eq!(board.get(1, 1), Some(Cell::X));
+        assert!(!board.set(1, 1, Cell::O)); // Can't overwrite
+    }
+
+    #[test]
+    fn test_winner_detection() {
+        let mut board = Board::new();
+
+        // Set up a winning row
+        board.set(0, 0, Cell::X);
+        board.set(0, 1, Cell::X);
+        board.set(0, 2, Cell::X);
+
+        assert_eq!(board.check_winner(), Some(Cell::X));
+    }
+
+    #[test]
+    fn test_all_winning_conditions() {
+        // Test all 8 possible winning combinations
+
+        // Test all rows
+        for row in 0..DEFAULT_SIZE {
+            let mut board = Board::new();
+            for col in 0..DEFAULT_SIZE {
+                board.set(row, col, Cell::O);
+            }
+            assert_eq!(
+                board.check_winner(),
+                Some(Cell::O),
+                "Row {} should be a win",
+                row
+            );
+        }
+
+        // Test all columns
+        for col in 0..DEFAULT_SIZE {
+            let mut board = Board::new();
+            for row in 0..DEFAULT_SIZE {
+                board.set(row, col, Cell::X);
+            }
+            assert_eq!(
+                board.check_winner(),
+                Some(Cell::X),
+                "Column {} should be a win",
+                col
+            );
+        }
+
+        // Test main diagonal (top-left to bottom-right)
+        let mut board = Board::new();
+        for i in 0..DEFAULT_SIZE {
+            board.set(i, i, Cell::O);
+        }
+        assert_eq!(
+            board.check_winner(),
+            Some(Cell::O),
+            "Main diagonal should be a win"
+        );
+
+        // Test anti-diagonal (top-right to bottom-left)
+        let mut board = Board::new();
+        for i in 0..DEFAULT_SIZE {
+            board.set(i, DEFAULT_SIZE - 1 - i, Cell::X);
+        }
+        assert_eq!(
+            board.check_winner(),
+            Some(Cell::X),
+            "Anti-diagonal should be a win"
+        );
+    }
+
+    #[test]
+    fn test_draw_detection() {
+        let mut board = Board::new();
+
+        // Create a draw scenario: X O X / O X O / O X O
+        board.set(0, 0, Cell::X);
+        board.set(0, 1, Cell::O);
+        board.set(0, 2, Cell::X);
+        board.set(1, 0, Cell::O);
         board.set(1, 1, Cell::X);
         board.set(1, 2, Cell::O);
         board.set(2, 0, Cell::O);
@@ -267,4 +1248,915 @@ mod tests {
         assert!(board.check_winner().is_none());
         assert!(board.is_game_over());
     }
+
+    #[test]
+    fn test_all_winners_reports_every_line() {
+        let mut board = Board::new();
+        for col in 0..board.size() {
+            board.set(0, col, Cell::X);
+            board.set(1, col, Cell::X);
+            board.set(2, col, Cell::O);
+        }
+
+        let winners = board.all_winners();
+        assert_eq!(
+            winners,
+            vec![
+                (Cell::X, vec![(0, 0), (0, 1), (0, 2)]),
+                (Cell::X, vec![(1, 0), (1, 1), (1, 2)]),
+                (Cell::O, vec![(2, 0), (2, 1), (2, 2)]),
+            ]
+        );
+        assert!(!board.is_legal_position());
+    }
+
+    #[test]
+    fn test_legal_position() {
+        let mut board = Board::new();
+        assert!(board.is_legal_position());
+
+        board.set(0, 0, Cell::X);
+        board.set(1, 1, Cell::O);
+        assert!(board.is_legal_position());
+
+        // X two marks ahead can't happen with alternating turns
+        board.set(2, 2, Cell::X);
+        board.set(0, 2, Cell::X);
+        assert!(!board.is_legal_position());
+    }
+
+    #[test]
+    fn test_swap_players() {
+        let mut board = Board::new();
+        board.set(0, 0, Cell::X);
+        board.set(1, 1, Cell::O);
+
+        let swapped = board.swap_players();
+        assert_eq!(swapped.get(0, 0), Some(Cell::O));
+        assert_eq!(swapped.get(1, 1), Some(Cell::X));
+        assert!(swapped.is_empty(2, 2));
+        assert_eq!(swapped.swap_players(), board);
+    }
+
+    #[test]
+    fn test_is_successor() {
+        let mut board = Board::new();
+        board.set(0, 0, Cell::X);
+
+        let mut next = board.clone();
+        next.set(1, 1, Cell::O);
+        assert_eq!(board.is_successor(&next), Some((1, 1, Cell::O)));
+
+        // No change
+        assert_eq!(board.is_successor(&board), None);
+
+        // Two new marks
+        let mut two_moves = next.clone();
+        two_moves.set(2, 2, Cell::X);
+        assert_eq!(board.is_successor(&two_moves), None);
+
+        // A mark removed rather than placed
+        assert_eq!(next.is_successor(&board), None);
+
+        // A mark overwritten
+        let mut overwritten = Board::new();
+        overwritten.set(0, 0, Cell::O);
+        assert_eq!(board.is_successor(&overwritten), None);
+    }
+
+    #[test]
+    fn test_open_line_count() {
+        let mut board = Board::new();
+        assert_eq!(board.open_line_count(Cell::X), 0);
+
+        board.set(1, 1, Cell::X);
+        assert_eq!(board.open_line_count(Cell::X), 4);
+
+        // O in a corner blocks the main diagonal
+        board.set(0, 0, Cell::O);
+        assert_eq!(board.open_line_count(Cell::X), 3);
+        assert_eq!(board.open_line_count(Cell::O), 2);
+    }
+
+    #[test]
+    fn test_open_lines_for() {
+        // X has two in the top row; O blocks every other line through an X
+        let board = Board::from_str_grid("XX.\nOO.\nX.O").unwrap();
+        let lines = board.open_lines_for(Cell::X);
+        assert_eq!(lines.len(), 1);
+        assert_eq!(lines[0].cells, vec![(0, 0), (0, 1), (0, 2)]);
+        assert_eq!(lines[0].marks, 2);
+        assert_eq!(lines[0].empty(), 1);
+
+        // O's middle row is a threat, its right column only a single mark
+        let counts: Vec<usize> = board
+            .open_lines_for(Cell::O)
+            .iter()
+            .map(|line| line.marks)
+            .collect();
+        assert_eq!(counts, vec![2, 1]);
+    }
+
+    #[test]
+    fn test_has_winning_possibility() {
+        assert!(Board::new().has_winning_possibility());
+        assert!(Board::from_str_grid("XOX\nXOO\nO..")
+            .unwrap()
+            .has_winning_possibility());
+
+        // Every line holds both marks, so neither side can complete one
+        let blocked = Board::from_str_grid("XOX\nXOO\nOX.").unwrap();
+        assert!(!blocked.has_winning_possibility());
+        let blocked = Board::from_str_grid("XO..\n..OX\nOX..\n..XO").unwrap();
+        assert!(!blocked.has_winning_possibility());
+        assert!(!blocked.is_game_over());
+    }
+
+    #[test]
+    fn test_winning_moves_for() {
+        let board = Board::from_str_grid("XX.\nO..\nO..").unwrap();
+        assert_eq!(board.winning_moves_for(Cell::X), vec![(0, 2)]);
+
+        // X threatens both the left column and the bottom row
+        let fork = Board::from_str_grid("X.O\n.O.\nX.X").unwrap();
+        assert_eq!(fork.winning_moves_for(Cell::X), vec![(1, 0), (2, 1)]);
+        assert_eq!(fork.winning_moves_for(Cell::O), vec![]);
+
+        assert!(Board::new().winning_moves_for(Cell::X).is_empty());
+    }
+
+    #[test]
+    fn test_empty_count() {
+        let mut board = Board::new();
+        assert_eq!(board.empty_count(), 9);
+        for (row, col, cell) in [(1, 1, Cell::X), (0, 0, Cell::O), (2, 1, Cell::X)] {
+            board.set(row, col, cell);
+            assert_eq!(board.empty_count(), board.empty_positions().len());
+        }
+        assert_eq!(board.empty_count(), 6);
+
+        let full = Board::from_str_grid("XOX\nXOO\nOXX").unwrap();
+        assert_eq!(full.empty_count(), 0);
+        assert_eq!(Board::with_size(4).unwrap().empty_count(), 16);
+    }
+
+    #[test]
+    fn test_would_win() {
+        let board = Board::from_str_grid("XX.\nOO.\n...").unwrap();
+        assert!(board.would_win(0, 2, Cell::X));
+        assert!(board.would_win(1, 2, Cell::O));
+        assert!(!board.would_win(2, 2, Cell::X));
+        assert!(!board.would_win(1, 2, Cell::X));
+
+        // Occupied and off-board cells never win
+        assert!(!board.would_win(0, 0, Cell::X));
+        assert!(!board.would_win(0, 3, Cell::X));
+        assert!(!board.would_win(0, 2, Cell::Empty));
+        assert_eq!(board.get(0, 2), Some(Cell::Empty));
+    }
+
+    #[test]
+    fn test_creates_fork() {
+        // X holds opposite corners and O has just taken a third corner
+        let board = Board::from_str_grid("X.O\n.O.\n..X").unwrap();
+
+        // Blocking at (2, 0) also threatens (1, 0) and (2, 1)
+        assert!(board.creates_fork(Cell::X, (2, 0)));
+        // Only threatens (2, 0)
+        assert!(!board.creates_fork(Cell::X, (1, 0)));
+        assert!(!board.creates_fork(Cell::X, (1, 1)));
+        assert!(!board.creates_fork(Cell::X, (5, 5)));
+    }
+
+    #[test]
+    fn test_drop_stacks_pieces() {
+        let mut board = Board::new();
+        assert_eq!(board.landing_positions(), vec![(2, 0), (2, 1), (2, 2)]);
+
+        assert_eq!(board.drop(1, Cell::X), Some(2));
+        assert_eq!(board.drop(1, Cell::O), Some(1));
+        assert_eq!(board.get(2, 1), Some(Cell::X));
+        assert_eq!(board.get(1, 1), Some(Cell::O));
+        assert_eq!(board.landing_positions(), vec![(2, 0), (0, 1), (2, 2)]);
+
+        // A full column rejects further drops
+        assert_eq!(board.drop(1, Cell::X), Some(0));
+        assert_eq!(board.drop(1, Cell::O), None);
+        assert_eq!(board.landing_row(1), None);
+        assert_eq!(board.plies_from_start(), 3);
+        assert_eq!(board.landing_positions(), vec![(2, 0), (2, 2)]);
+
+        assert_eq!(board.drop(3, Cell::X), None);
+
+        // Drops complete lines like any other placement
+        let mut board = Board::new();
+        for col in 0..3 {
+            board.drop(col, Cell::X);
+        }
+        assert_eq!(board.check_winner(), Some(Cell::X));
+    }
+
+    #[test]
+    fn test_rotations_and_flips() {
+        let board = Board::from_str_grid("XO.\n..X\n...").unwrap();
+        assert_eq!(board.rotated_cw().to_str_grid(), "..X\n..O\n.X.");
+        assert_eq!(board.rotated_ccw().to_str_grid(), ".X.\nO..\nX..");
+        assert_eq!(board.flipped_horizontal().to_str_grid(), ".OX\nX..\n...");
+        assert_eq!(board.flipped_vertical().to_str_grid(), "...\n..X\nXO.");
+
+        let mut turned = board.clone();
+        for _ in 0..4 {
+            turned = turned.rotated_cw();
+        }
+        assert_eq!(turned, board);
+        assert_eq!(board.rotated_cw().rotated_ccw(), board);
+        assert_eq!(board.flipped_horizontal().flipped_horizontal(), board);
+        assert_eq!(board.flipped_vertical().flipped_vertical(), board);
+
+        let mut large = Board::with_size(4).unwrap();
+        large.set(0, 0, Cell::O);
+        assert_eq!(large.rotated_cw().get(0, 3), Some(Cell::O));
+        assert_eq!(large.flipped_vertical().get(3, 0), Some(Cell::O));
+    }
+
+    #[test]
+    fn test_is_symmetric_to() {
+        let corner = Board::from_str_grid("X..\n...\n...").unwrap();
+        let other_corner = Board::from_str_grid("..X\n...\n...").unwrap();
+        assert!(corner.is_symmetric_to(&other_corner));
+        assert!(corner.is_symmetric_to(&corner.rotated_cw()));
+        assert!(corner.is_symmetric_to(&corner));
+
+        // Reflected across the main diagonal
+        let board = Board::from_str_grid("XO.\n...\n...").unwrap();
+        assert!(board.is_symmetric_to(&Board::from_str_grid("X..\nO..\n...").unwrap()));
+
+        // Different marks or layouts
+        let o_corner = Board::from_str_grid("O..\n...\n...").unwrap();
+        assert!(!corner.is_symmetric_to(&o_corner));
+        let edge = Board::from_str_grid(".X.\n...\n...").unwrap();
+        assert!(!corner.is_symmetric_to(&edge));
+        assert!(!Board::new().is_symmetric_to(&Board::with_size(4).unwrap()));
+    }
+
+    #[test]
+    #[cfg(feature = "serde")]
+    fn test_serde_round_trip() {
+        let board = Board::from_str_grid("XO..\n.X..\n..O.\n....").unwrap();
+        let json = serde_json::to_string(&board).unwrap();
+        assert_eq!(serde_json::from_str::<Board>(&json).unwrap(), board);
+        assert_eq!(serde_json::to_string(&Cell::X).unwrap(), "\"X\"");
+
+        // Fields that don't fit together are rejected
+        for bad in [
+            r#"{"size":3,"win_length":3,"cells":["X"]}"#,
+            r#"{"size":3,"win_length":4,"cells":[]}"#,
+            r#"{"size":2,"win_length":2,"cells":["X","O","X","O"]}"#,
+        ] {
+            assert!(serde_json::from_str::<Board>(bad).is_err());
+        }
+    }
+
+    #[test]
+    #[cfg(feature = "std")]
+    fn test_hash_matches_equality() {
+        use std::collections::hash_map::DefaultHasher;
+        use std::hash::{Hash, Hasher};
+
+        let hash = |board: &Board| {
+            let mut hasher = DefaultHasher::new();
+            board.hash(&mut hasher);
+            hasher.finish()
+        };
+
+        let board = Board::from_str_grid("XO.\n.X.\n..O").unwrap();
+        let same = Board::from_str_grid("XO.\n.X.\n..O").unwrap();
+        assert_eq!(hash(&board), hash(&same));
+        assert_ne!(hash(&board), hash(&board.rotated_cw()));
+    }
+
+    #[test]
+    fn test_encode_decode_round_trip() {
+        assert_eq!(Board::new().encode(), 0);
+
+        // The first cell is the most significant digit
+        let corner = Board::from_str_grid("O..\n...\n...").unwrap();
+        assert_eq!(corner.encode(), 2 * 3u32.pow(8));
+        let last = Board::from_str_grid("...\n...\n..X").unwrap();
+        assert_eq!(last.encode(), 1);
+
+        for grid in ["XO.\n.X.\n..O", "XOX\nOXO\nOXO", "...\n.X.\n..."] {
+            let board = Board::from_str_grid(grid).unwrap();
+            assert_eq!(Board::decode(board.encode()), board);
+        }
+        assert_eq!(
+            Board::decode(3u32.pow(9) - 1).to_str_grid(),
+            "OOO\nOOO\nOOO"
+        );
+    }
+
+    #[test]
+    fn test_count_and_valid_state() {
+        let board = Board::from_str_grid("XO.\n.X.\n..O").unwrap();
+        assert_eq!(board.count(Cell::X), 2);
+        assert_eq!(board.count(Cell::O), 2);
+        assert_eq!(board.count(Cell::Empty), 5);
+        assert!(board.is_valid_state());
+
+        // X has two more marks than O
+        let ahead = Board::from_str_grid("XX.\n.X.\n..O").unwrap();
+        assert!(!ahead.is_valid_state());
+
+        // Both players completed a line
+        let both = Board::from_str_grid("XXX\nOOO\n...").unwrap();
+        assert!(!both.is_valid_state());
+    }
+
+    #[test]
+    fn test_heuristic_score() {
+        let mut board = Board::new();
+        assert_eq!(board.heuristic_score(Cell::X), 0);
+
+        board.set(1, 1, Cell::X);
+        board.set(0, 0, Cell::O);
+        assert_eq!(board.heuristic_score(Cell::X), 1);
+        assert_eq!(board.heuristic_score(Cell::O), -1);
+        assert_eq!(board.heuristic_score(Cell::Empty), 0);
+    }
+
+    #[test]
+    fn test_first_empty() {
+        let mut board = Board::new();
+        assert_eq!(board.first_empty(), Some((0, 0)));
+
+        board.set(0, 0, Cell::X);
+        board.set(0, 1, Cell::O);
+        assert_eq!(board.first_empty(), Some((0, 2)));
+
+        for (row, col) in board.empty_positions() {
+            board.set(row, col, Cell::X);
+        }
+        assert_eq!(board.first_empty(), None);
+    }
+
+    #[test]
+    fn test_check_winner_near_on_large_board() {
+        let mut board = Board::with_win_length(15, 5).unwrap();
+        for col in 3..7 {
+            board.set(7, col, Cell::X);
+        }
+        assert_eq!(board.check_winner_near((7, 6)), None);
+
+        // The fifth mark completes the run, whichever end it was placed at
+        board.set(7, 7, Cell::X);
+        assert_eq!(board.check_winner_near((7, 7)), Some(Cell::X));
+        assert_eq!(board.check_winner_near((7, 3)), Some(Cell::X));
+        assert_eq!(board.check_winner(), Some(Cell::X));
+
+        // A sixth mark in the run still wins
+        board.set(7, 8, Cell::X);
+        assert_eq!(board.check_winner_near((7, 8)), Some(Cell::X));
+
+        // An anti-diagonal run, checked from its middle
+        let mut board = Board::with_win_length(15, 5).unwrap();
+        for i in 0..5 {
+            board.set(10 - i, 2 + i, Cell::O);
+        }
+        assert_eq!(board.check_winner_near((8, 4)), Some(Cell::O));
+
+        // Only lines through the given cell are looked at
+        board.set(0, 14, Cell::X);
+        assert_eq!(board.check_winner_near((0, 14)), None);
+        assert_eq!(board.check_winner_near((0, 0)), None);
+    }
+
+    #[test]
+    fn test_next_to_move() {
+        assert_eq!(Board::new().next_to_move(), Some(Cell::X));
+        assert_eq!(
+            Board::from_str_grid("X..\n...\n...")
+                .unwrap()
+                .next_to_move(),
+            Some(Cell::O)
+        );
+        assert_eq!(
+            Board::from_str_grid("XX.\n...\n...")
+                .unwrap()
+                .next_to_move(),
+            None
+        );
+    }
+
+    #[test]
+    fn test_side_to_move() {
+        let mut board = Board::new();
+        assert_eq!(board.side_to_move(true), Ok(Cell::X));
+        assert_eq!(board.side_to_move(false), Ok(Cell::O));
+
+        board.set(0, 0, Cell::X);
+        assert_eq!(board.side_to_move(true), Ok(Cell::O));
+        // O can't be behind when it moved first
+        assert_eq!(
+            board.side_to_move(false),
+            Err(BoardError::ImpossibleMarkCounts)
+        );
+
+        board.set(1, 1, Cell::O);
+        assert_eq!(board.side_to_move(true), Ok(Cell::X));
+
+        board.set(2, 2, Cell::X);
+        board.set(0, 2, Cell::X);
+        assert_eq!(
+            board.side_to_move(true),
+            Err(BoardError::ImpossibleMarkCounts)
+        );
+    }
+
+    #[test]
+    fn test_diff() {
+        let mut before = Board::new();
+        before.set(0, 0, Cell::X);
+        let mut after = before.clone();
+        after.set(1, 2, Cell::O);
+
+        assert_eq!(before.diff(&after), vec![(1, 2, Cell::Empty, Cell::O)]);
+        assert!(before.diff(&before).is_empty());
+    }
+
+    #[test]
+    fn test_diff_display_highlights_changes() {
+        let mut before = Board::new();
+        before.set(0, 0, Cell::X);
+        let mut after = before.clone();
+        after.set(1, 2, Cell::O);
+
+        let rendered = before.diff_display(&after);
+        let lines: Vec<&str> = rendered.lines().collect();
+        assert_eq!(lines.len(), 6);
+        assert_eq!(lines[0], "   0   1   2        0   1   2 ");
+        assert_eq!(lines[1], "0  X |   |       0  X |   |   ");
+        assert_eq!(lines[3], "1    |   |[ ]    1    |   |[O]");
+    }
+
+    #[test]
+    fn test_render_empty_board() {
+        let board = Board::new();
+        assert_eq!(
+            board.render(),
+            "  0   1   2\n0   |   |  \n  ---------\n1   |   |  \n  ---------\n2   |   |  \n"
+        );
+        assert_eq!(board.render(), board.to_string());
+    }
+
+    #[test]
+    fn test_render_styled() {
+        let mut board = Board::new();
+        board.set(0, 0, Cell::X);
+        board.set(1, 1, Cell::O);
+        let is_box_drawing = |c: char| ('\u{2500}'..='\u{257f}').contains(&c);
+
+        let ascii = board.render_styled(RenderStyle::Ascii);
+        assert_eq!(ascii, board.render());
+        assert!(!ascii.chars().any(is_box_drawing));
+
+        let unicode = board.render_styled(RenderStyle::Unicode);
+        assert!(unicode.chars().any(is_box_drawing));
+        assert!(unicode.contains("0 X │   │  \n  ──┼───┼──\n"));
+
+        let colored = board.render_styled(RenderStyle::Colored);
+        assert!(colored.contains("\x1b[31mX\x1b[0m"));
+        assert!(colored.contains("\x1b[34mO\x1b[0m"));
+    }
+
+    #[test]
+    fn test_render_with_origin() {
+        let mut board = Board::new();
+        board.set(1, 1, Cell::X);
+
+        let zero_based = board.render_with_origin(false);
+        assert_eq!(zero_based, board.to_string());
+        assert!(zero_based.starts_with("  0   1   2\n0 "));
+
+        let one_based = board.render_with_origin(true);
+        assert!(one_based.starts_with("  1   2   3\n1 "));
+        assert!(one_based.contains("2   | X |  "));
+    }
+
+    #[test]
+    fn test_move_conversions() {
+        let mv = Move::new(1, 2);
+        assert_eq!(Move::from((1, 2)), mv);
+        assert_eq!(<(usize, usize)>::from(mv), (1, 2));
+        assert_eq!(mv.index(DEFAULT_SIZE), 5);
+        assert_eq!(Move::from_index(5, DEFAULT_SIZE), mv);
+        assert_eq!(Move::from_index(0, DEFAULT_SIZE), Move::new(0, 0));
+    }
+
+    #[test]
+    fn test_set_at() {
+        let mut board = Board::new();
+        assert!(board.set_at(Move::new(2, 0), Cell::X));
+        assert!(board.set_at((0, 2), Cell::O));
+        assert!(!board.set_at(Move::new(2, 0), Cell::O));
+        assert_eq!(board.get(2, 0), Some(Cell::X));
+        assert_eq!(board.get(0, 2), Some(Cell::O));
+    }
+
+    #[test]
+    fn test_games_of_length() {
+        let games = Board::games_of_length(1, true);
+        assert_eq!(games.len(), 9);
+        assert!(games.iter().all(|game| game.moves()[0].2 == Cell::X));
+
+        assert_eq!(Board::games_of_length(2, false).len(), 72);
+        assert_eq!(Board::games_of_length(0, true).len(), 1);
+
+        // Some five-ply games end in a win and can't be extended to six plies
+        let games = Board::games_of_length(6, true);
+        assert!(games.len() < 9 * 8 * 7 * 6 * 5 * 4);
+        assert!(games.iter().all(|game| game.is_legal() && game.len() == 6));
+    }
+
+    #[test]
+    fn test_influence() {
+        assert_eq!(
+            Board::new().influence(Cell::X),
+            [[3, 2, 3], [2, 4, 2], [3, 2, 3]]
+        );
+
+        let mut board = Board::new();
+        board.set(1, 1, Cell::X);
+        assert_eq!(board.influence(Cell::X), [[3, 2, 3], [2, 0, 2], [3, 2, 3]]);
+        assert_eq!(board.influence(Cell::O), [[2, 1, 2], [1, 0, 1], [2, 1, 2]]);
+    }
+
+    #[test]
+    fn test_winning_masks() {
+        let masks = Board::winning_masks();
+        assert_eq!(masks.len(), 8);
+        assert!(masks.iter().all(|mask| mask.count_ones() == 3));
+        assert_eq!(masks[0], 0b000_000_111);
+        assert_eq!(masks[6], 0b100_010_001);
+
+        let mut board = Board::new();
+        board.set(0, 2, Cell::O);
+        board.set(1, 1, Cell::O);
+        board.set(2, 0, Cell::O);
+        board.set(0, 0, Cell::X);
+        let o_mask = board.occupancy(Cell::O);
+        assert_eq!(o_mask, 0b001_010_100);
+        assert_eq!(
+            masks.iter().filter(|&&mask| mask & o_mask == mask).count(),
+            1
+        );
+        assert!(masks
+            .iter()
+            .all(|&mask| mask & board.occupancy(Cell::X) != mask));
+    }
+
+    #[test]
+    fn test_hash_without() {
+        let mut cleared = Board::new();
+        cleared.set(0, 0, Cell::X);
+        cleared.set(2, 1, Cell::O);
+
+        let mut board = cleared.clone();
+        board.set(1, 1, Cell::X);
+
+        assert_ne!(board.zobrist_hash(), cleared.zobrist_hash());
+        assert_eq!(board.hash_without(1, 1), cleared.zobrist_hash());
+
+        // Empty or off-board positions leave the hash unchanged
+        assert_eq!(board.hash_without(2, 2), board.zobrist_hash());
+        assert_eq!(board.hash_without(5, 5), board.zobrist_hash());
+        assert_eq!(Board::new().zobrist_hash(), 0);
+
+        // X and O on the same cell hash differently
+        let mut x_board = Board::new();
+        x_board.set(1, 1, Cell::X);
+        assert_ne!(
+            x_board.zobrist_hash(),
+            x_board.swap_players().zobrist_hash()
+        );
+    }
+
+    #[test]
+    fn test_plies_from_start_and_remaining() {
+        let mut board = Board::new();
+        assert_eq!(board.plies_from_start(), 0);
+        assert_eq!(board.remaining_plies(), 9);
+
+        board.set(1, 1, Cell::X);
+        assert_eq!(board.plies_from_start(), 1);
+        assert_eq!(board.remaining_plies(), 8);
+
+        board.set(0, 0, Cell::O);
+        board.set(2, 2, Cell::X);
+        board.set(0, 2, Cell::O);
+        assert_eq!(board.plies_from_start(), 4);
+        assert_eq!(board.remaining_plies(), 5);
+
+        let moves = [
+            (0, 0, Cell::X),
+            (0, 1, Cell::O),
+            (0, 2, Cell::X),
+            (1, 1, Cell::O),
+            (1, 0, Cell::X),
+            (1, 2, Cell::O),
+            (2, 1, Cell::X),
+            (2, 0, Cell::O),
+            (2, 2, Cell::X),
+        ];
+        let mut full = Board::new();
+        for (row, col, cell) in moves {
+            full.set(row, col, cell);
+        }
+        assert_eq!(full.plies_from_start(), 9);
+        assert_eq!(full.remaining_plies(), 0);
+    }
+
+    #[test]
+    fn test_with_size() {
+        let mut board = Board::with_size(4).unwrap();
+        assert_eq!(board.size(), 4);
+        assert_eq!(Board::new().size(), 3);
+        assert_eq!(board.empty_positions().len(), 16);
+        assert_eq!(board.get(3, 3), Some(Cell::Empty));
+        assert_eq!(board.get(4, 0), None);
+        assert!(!board.set(0, 4, Cell::X));
+
+        // Three in a row no longer fills a line
+        for col in 0..3 {
+            board.set(1, col, Cell::X);
+        }
+        assert_eq!(board.check_winner(), None);
+        board.set(1, 3, Cell::X);
+        assert_eq!(board.check_winner(), Some(Cell::X));
+
+        let mut board = Board::with_size(4).unwrap();
+        for i in 0..4 {
+            board.set(i, 3 - i, Cell::O);
+        }
+        assert_eq!(board.check_winner(), Some(Cell::O));
+        assert_eq!(board.remaining_plies(), 12);
+        assert!(!board.is_full());
+    }
+
+    #[test]
+    fn test_with_size_rejects_small_boards() {
+        assert_eq!(Board::with_size(0), Err(BoardError::InvalidSize));
+        assert_eq!(Board::with_size(2), Err(BoardError::InvalidSize));
+        assert_eq!(Board::with_size(3), Ok(Board::new()));
+        assert_eq!(
+            Board::from_str_grid("XO\nOX"),
+            Err(ParseBoardError::TooSmall(2))
+        );
+
+        assert_eq!(
+            Board::with_win_length(4, 5),
+            Err(BoardError::InvalidWinLength)
+        );
+        assert_eq!(
+            Board::with_win_length(4, 0),
+            Err(BoardError::InvalidWinLength)
+        );
+        let board = Board::with_win_length(15, 5).unwrap();
+        assert_eq!((board.size(), board.win_length()), (15, 5));
+    }
+
+    #[test]
+    fn test_with_size_full_and_display() {
+        let mut board = Board::with_size(3).unwrap();
+        for (row, col, cell) in Board::from_str_grid("XOX\nOOX\nXXO").unwrap().iter_cells() {
+            board.set(row, col, cell);
+        }
+        assert!(board.is_full());
+        assert!(board.empty_positions().is_empty());
+        assert_eq!(
+            board.to_string(),
+            "  0   1   2\n0 X | O | X\n  ---------\n1 O | O | X\n  ---------\n2 X | X | O\n"
+        );
+
+        let rendered = Board::with_size(5).unwrap().to_string();
+        assert!(rendered.starts_with("  0   1   2   3   4\n"));
+        assert_eq!(rendered.lines().count(), 10);
+    }
+
+    #[test]
+    fn test_win_length_on_larger_board() {
+        let mut board = Board::with_win_length(5, 4).unwrap();
+        assert_eq!(board.win_length(), 4);
+        assert_eq!(Board::with_size(5).unwrap().win_length(), 5);
+
+        for col in 1..4 {
+            board.set(2, col, Cell::X);
+        }
+        assert_eq!(board.check_winner(), None);
+
+        // Four in a row wins even without touching the edge of the board
+        board.set(2, 4, Cell::X);
+        assert_eq!(board.check_winner(), Some(Cell::X));
+
+        let mut board = Board::with_win_length(5, 4).unwrap();
+        for i in 0..4 {
+            board.set(i + 1, 3 - i, Cell::O);
+        }
+        assert_eq!(board.check_winner(), Some(Cell::O));
+    }
+
+    #[test]
+    fn test_win_length_lines() {
+        // 4 rows, 4 columns and 2 + 2 diagonals fit a run of three on a 4x4 board
+        let board = Board::with_win_length(4, 3).unwrap();
+        assert_eq!(board.lines().len(), 2 * 4 * 2 + 4 * 2);
+        assert_eq!(Board::new().lines().len(), 8);
+        assert_eq!(board.open_line_count(Cell::X), 0);
+
+        let lines = Board::new().lines();
+        assert!(lines.contains(&vec![(0, 0), (1, 1), (2, 2)]));
+    }
+
+    #[test]
+    fn test_winning_line() {
+        assert_eq!(Board::new().winning_line(), None);
+
+        let mut board = Board::new();
+        for i in 0..3 {
+            board.set(i, i, Cell::X);
+        }
+        assert_eq!(
+            board.winning_line(),
+            Some((Cell::X, vec![(0, 0), (1, 1), (2, 2)]))
+        );
+
+        let mut board = Board::new();
+        for i in 0..3 {
+            board.set(i, 2 - i, Cell::O);
+        }
+        assert_eq!(
+            board.winning_line(),
+            Some((Cell::O, vec![(0, 2), (1, 1), (2, 0)]))
+        );
+
+        let mut board = Board::new();
+        for i in 0..3 {
+            board.set(1, i, Cell::X);
+        }
+        assert_eq!(
+            board.winning_line(),
+            Some((Cell::X, vec![(1, 0), (1, 1), (1, 2)]))
+        );
+
+        let mut board = Board::new();
+        for i in 0..3 {
+            board.set(i, 2, Cell::O);
+        }
+        assert_eq!(
+            board.winning_line(),
+            Some((Cell::O, vec![(0, 2), (1, 2), (2, 2)]))
+        );
+
+        // Runs shorter than the board report only the winning cells
+        let mut board = Board::with_win_length(5, 4).unwrap();
+        for i in 0..4 {
+            board.set(i + 1, i + 1, Cell::X);
+        }
+        assert_eq!(
+            board.winning_line(),
+            Some((Cell::X, vec![(1, 1), (2, 2), (3, 3), (4, 4)]))
+        );
+    }
+
+    #[test]
+    fn test_str_grid_round_trip() {
+        let board = Board::from_str_grid("XOX\n.O.\nX.O").unwrap();
+        assert_eq!(board.get(0, 1), Some(Cell::O));
+        assert_eq!(board.get(1, 0), Some(Cell::Empty));
+        assert_eq!(board.get(2, 2), Some(Cell::O));
+        assert_eq!(board.to_str_grid(), "XOX\n.O.\nX.O");
+        assert_eq!(Board::from_str_grid(&board.to_str_grid()), Ok(board));
+
+        // Spaces also mark empty cells, and the row length sets the size
+        let board = Board::from_str_grid("X  O\n    \n    \nO  X\n").unwrap();
+        assert_eq!(board.size(), 4);
+        assert_eq!(board.to_str_grid(), "X..O\n....\n....\nO..X");
+        assert_eq!(Board::new().to_str_grid(), "...\n...\n...");
+    }
+
+    #[test]
+    fn test_str_grid_rejects_malformed_input() {
+        assert_eq!(Board::from_str_grid(""), Err(ParseBoardError::Empty));
+        assert_eq!(
+            Board::from_str_grid("XOX\n.O."),
+            Err(ParseBoardError::WrongRowCount {
+                expected: 3,
+                found: 2
+            })
+        );
+        assert_eq!(
+            Board::from_str_grid("XOX\n.O\nX.O"),
+            Err(ParseBoardError::WrongRowLength {
+                row: 1,
+                expected: 3,
+                found: 2
+            })
+        );
+
+        let error = Board::from_str_grid("XOX\n.Q.\nX.O").unwrap_err();
+        assert_eq!(
+            error,
+            ParseBoardError::InvalidChar {
+                row: 1,
+                col: 1,
+                found: 'Q'
+            }
+        );
+        assert!(error.to_string().contains("'Q'"));
+    }
+
+    #[test]
+    fn test_cell_opponent() {
+        assert_eq!(Cell::X.opponent(), Cell::O);
+        assert_eq!(Cell::O.opponent(), Cell::X);
+        assert_eq!(Cell::Empty.opponent(), Cell::Empty);
+    }
+
+    #[test]
+    fn test_cell_from_str() {
+        assert_eq!("X".parse(), Ok(Cell::X));
+        assert_eq!("x".parse(), Ok(Cell::X));
+        assert_eq!("O".parse(), Ok(Cell::O));
+        assert_eq!("o".parse(), Ok(Cell::O));
+        assert_eq!(" ".parse(), Ok(Cell::Empty));
+        assert_eq!(".".parse(), Ok(Cell::Empty));
+        assert_eq!("".parse(), Ok(Cell::Empty));
+
+        let error = "XO".parse::<Cell>().unwrap_err();
+        assert_eq!(
+            error,
+            ParseCellError {
+                input: "XO".to_string()
+            }
+        );
+        assert!(error.to_string().contains("\"XO\""));
+
+        // Display output parses back to the same cell
+        for cell in [Cell::Empty, Cell::X, Cell::O] {
+            assert_eq!(cell.to_string().parse(), Ok(cell));
+        }
+    }
+
+    #[test]
+    fn test_iter_cells() {
+        let board = Board::from_str_grid("X..\n.O.\n..X").unwrap();
+
+        let cells: Vec<_> = board.iter_cells().collect();
+        assert_eq!(cells.len(), 9);
+        assert_eq!(cells[0], (0, 0, Cell::X));
+        assert_eq!(cells[1], (0, 1, Cell::Empty));
+        assert_eq!(cells[3], (1, 0, Cell::Empty));
+        assert_eq!(cells[8], (2, 2, Cell::X));
+
+        let occupied: Vec<_> = board.iter_occupied().collect();
+        assert_eq!(
+            occupied,
+            vec![(0, 0, Cell::X), (1, 1, Cell::O), (2, 2, Cell::X)]
+        );
+
+        let mut large = Board::with_size(4).unwrap();
+        large.set(3, 2, Cell::O);
+        assert_eq!(large.iter_cells().count(), 16);
+        assert_eq!(
+            large.iter_occupied().collect::<Vec<_>>(),
+            vec![(3, 2, Cell::O)]
+        );
+    }
+
+    #[test]
+    fn test_symmetries() {
+        let distinct = |board: &Board| {
+            let mut boards = board.symmetries();
+            assert_eq!(boards.len(), 8);
+            assert_eq!(boards[0], *board);
+            boards.sort_by_key(Board::to_str_grid);
+            boards.dedup();
+            boards.len()
+        };
+
+        let asymmetric = Board::from_str_grid("XO.\n...\n...").unwrap();
+        assert_eq!(distinct(&asymmetric), 8);
+        assert_eq!(distinct(&Board::new()), 1);
+        assert_eq!(distinct(&Board::from_str_grid("...\n.X.\n...").unwrap()), 1);
+        assert_eq!(distinct(&Board::from_str_grid("X..\n...\n...").unwrap()), 4);
+
+        let rotated = &asymmetric.symmetries()[1];
+        assert_eq!(rotated.to_str_grid(), "..X\n..O\n...");
+        let mirrored = &asymmetric.symmetries()[4];
+        assert_eq!(mirrored.to_str_grid(), ".OX\n...\n...");
+
+        // Works on larger boards too
+        let mut large = Board::with_size(4).unwrap();
+        large.set(0, 1, Cell::X);
+        assert_eq!(large.symmetries()[2].get(3, 2), Some(Cell::X));
+    }
 }
@@ -0,0 +1,254 @@
+//! Database module - Precomputed values and best moves for every reachable position
+
+use crate::ai::{AiAgent, Position};
+use crate::board::{Board, Cell};
+use crate::solver::TwoPlayerGame;
+use std::collections::HashMap;
+use std::sync::OnceLock;
+
+/// The solved value and best move of a single position
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Entry {
+    /// The same score `AiAgent::evaluate` returns for the mark to move
+    pub value: i32,
+    /// The move `AiAgent::get_best_move_for` would play, or None once the game is over
+    pub best_move: Option<(usize, usize)>,
+}
+
+/// A solved position: its value and the score of every move, in reading order
+struct Solved {
+    /// The same score `AiAgent::evaluate` returns for the mark to move
+    value: i32,
+    /// Each empty position with the score `AiAgent` gives playing there
+    scored_moves: Vec<((usize, usize), i32)>,
+}
+
+/// Every position reachable from the empty board, with either mark moving first,
+/// solved once and shared for the rest of the program
+/// Positions are stored once per symmetry class, under the image with the smallest
+/// `Board::encode`, and mapped back to the board asked about on lookup
+pub struct PositionDatabase {
+    entries: HashMap<(Board, Cell), Solved>,
+}
+
+impl PositionDatabase {
+    /// Returns the solved entry for `board` with `to_move` to play,
+    /// or None if the position can't arise from alternating play on a 3x3 board
+    pub fn lookup(board: &Board, to_move: Cell) -> Option<Entry> {
+        let solved = Self::solved(board, to_move)?;
+        Some(Entry {
+            value: solved.value,
+            best_move: AiAgent::pick_best_move(board, solved.scored_moves, to_move),
+        })
+    }
+
+    /// Returns every empty position on `board` with the score `AiAgent` gives `to_move`
+    /// playing there, in reading order, or None if the position isn't in the database
+    pub(crate) fn move_scores(board: &Board, to_move: Cell) -> Option<Vec<((usize, usize), i32)>> {
+        Self::solved(board, to_move).map(|solved| solved.scored_moves)
+    }
+
+    /// Returns the number of solved positions, counting symmetric positions once
+    pub fn position_count() -> usize {
+        Self::global().entries.len()
+    }
+
+    /// Returns the shared database, building it on first use
+    fn global() -> &'static PositionDatabase {
+        static DATABASE: OnceLock<PositionDatabase> = OnceLock::new();
+        DATABASE.get_or_init(Self::build)
+    }
+
+    /// Returns the image of `board` positions are stored under, and the symmetry giving it
+    fn canonical(board: &Board) -> (Board, usize) {
+        (0..Board::SYMMETRY_COUNT)
+            .map(|symmetry| (board.transformed(symmetry), symmetry))
+            .min_by_key(|(image, _)| image.encode())
+            .expect("a board has symmetries")
+    }
+
+    /// Looks up the stored position and maps its moves back onto `board`
+    fn solved(board: &Board, to_move: Cell) -> Option<Solved> {
+        // Only the standard 3x3 game is solved
+        let standard = Board::new();
+        if (board.size(), board.win_length()) != (standard.size(), standard.win_length()) {
+            return None;
+        }
+
+        let (canonical, symmetry) = Self::canonical(board);
+        let solved = Self::global().entries.get(&(canonical, to_move))?;
+
+        // Turning back undoes a turn, and a mirrored turn undoes itself
+        let inverse = if symmetry < 4 {
+            (4 - symmetry) % 4
+        } else {
+            symmetry
+        };
+        let mut scored_moves: Vec<((usize, usize), i32)> = solved
+            .scored_moves
+            .iter()
+            .map(|&(stored, score)| (board.transform_position(inverse, stored), score))
+            .collect();
+        scored_moves.sort_unstable();
+        Some(Solved {
+            value: solved.value,
+            scored_moves,
+        })
+    }
+
+    /// Solves every reachable position and stores each symmetry class once
+    fn build() -> Self {
+        let mut values = HashMap::new();
+        for first in [Cell::X, Cell::O] {
+            let start = Position {
+                board: Board::new(),
+                to_move: first,
+            };
+            Self::solve(&start, &mut values);
+        }
+
+        let entries = values
+            .keys()
+            .filter(|(board, _)| Self::canonical(board).0 == *board)
+            .map(|(board, to_move)| {
+                let solved = Self::solved_from(board, *to_move, &values);
+                ((board.clone(), *to_move), solved)
+            })
+            .collect();
+        Self { entries }
+    }
+
+    /// Memoized negamax over `Position`, recording the value of every position visited
+    fn solve(position: &Position, values: &mut HashMap<(Board, Cell), i32>) -> i32 {
+        let key = (position.board.clone(), position.to_move);
+        if let Some(&value) = values.get(&key) {
+            return value;
+        }
+
+        let value = match position.terminal_value() {
+            Some(value) => value,
+            None => position
+                .moves()
+                .into_iter()
+                .map(|m| -Self::solve(&position.apply(m), values))
+                .max()
+                .unwrap_or(0),
+        };
+        values.insert(key, value);
+        value
+    }
+
+    /// Scores each move the way the AI does
+    fn solved_from(board: &Board, to_move: Cell, values: &HashMap<(Board, Cell), i32>) -> Solved {
+        if let Some(winner) = board.check_winner() {
+            let value = if winner == to_move { 100 } else { -100 };
+            return Solved {
+                value,
+                scored_moves: Vec::new(),
+            };
+        }
+
+        let opponent = to_move.opponent();
+        let scored_moves: Vec<((usize, usize), i32)> = board
+            .empty_positions()
+            .into_iter()
+            .map(|(row, col)| {
+                let mut child = board.clone();
+                child.set(row, col, to_move);
+                let score = -values[&(child.clone(), opponent)];
+
+                // Rescale to count plies from the child, as AiAgent scores do
+                let empty_count = child.remaining_plies() as i32;
+                let score = match score.signum() {
+                    1 => score - empty_count,
+                    -1 => score + empty_count,
+                    _ => 0,
+                };
+                ((row, col), score)
+            })
+            .collect();
+
+        Solved {
+            value: scored_moves
+                .iter()
+                .map(|&(_, score)| score)
+                .max()
+                .unwrap_or(0),
+            scored_moves,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_database_covers_reachable_positions() {
+        // 5478 positions are reachable with X first, 765 up to symmetry, and as many again
+        // with O first
+        assert_eq!(PositionDatabase::position_count(), 2 * 765);
+
+        for n in 0..=5 {
+            for record in Board::games_of_length(n, true) {
+                let board = record.final_board();
+                let to_move = if n % 2 == 0 { Cell::X } else { Cell::O };
+                assert!(PositionDatabase::lookup(&board, to_move).is_some());
+                assert!(
+                    PositionDatabase::lookup(&board.swap_players(), to_move.opponent()).is_some()
+                );
+            }
+        }
+    }
+
+    #[test]
+    fn test_lookup_matches_live_minimax() {
+        let ai = AiAgent::new();
+        for record in Board::games_of_length(3, true) {
+            let board = record.final_board();
+            let entry = PositionDatabase::lookup(&board, Cell::O).unwrap();
+            assert_eq!(entry.value, ai.evaluate(&board, Cell::O));
+            assert_eq!(entry.best_move, ai.get_best_move_for(&board, Cell::O));
+        }
+
+        let entry = PositionDatabase::lookup(&Board::new(), Cell::X).unwrap();
+        assert_eq!(entry.value, 0);
+        assert_eq!(
+            entry.best_move,
+            ai.get_best_move_for(&Board::new(), Cell::X)
+        );
+    }
+
+    #[test]
+    fn test_lookup_maps_moves_back_through_symmetries() {
+        let ai = AiAgent::new();
+        // X must block O's column on the right edge, wherever the board is turned
+        let board = Board::from_str_grid("X.O\n..O\nX..").unwrap();
+        for image in board.symmetries() {
+            let entry = PositionDatabase::lookup(&image, Cell::X).unwrap();
+            assert_eq!(entry.value, ai.evaluate(&image, Cell::X));
+            assert_eq!(entry.best_move, ai.get_best_move_for(&image, Cell::X));
+            assert_eq!(
+                PositionDatabase::move_scores(&image, Cell::X).unwrap(),
+                ai.score_moves(&image, Cell::X)
+            );
+        }
+        assert_eq!(
+            PositionDatabase::lookup(&Board::with_size(4).unwrap(), Cell::X),
+            None
+        );
+    }
+
+    #[test]
+    fn test_lookup_rejects_unreachable_positions() {
+        let mut board = Board::new();
+        board.set(0, 0, Cell::X);
+        board.set(1, 1, Cell::X);
+        assert_eq!(PositionDatabase::lookup(&board, Cell::O), None);
+
+        // Right marks, wrong side to move
+        board.set(2, 2, Cell::O);
+        assert_eq!(PositionDatabase::lookup(&board, Cell::X), None);
+        assert!(PositionDatabase::lookup(&board, Cell::O).is_some());
+    }
+}
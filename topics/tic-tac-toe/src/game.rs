@@ -1,27 +1,96 @@
 //! Game module - Main game logic
 
-use crate::ai::AiAgent;
-use crate::board::{Board, Cell};
+use crate::ai::{AiAgent, Difficulty, MoveQuality};
+pub use crate::board::RuleSet;
+use crate::board::{Board, Cell, Move};
+use crate::record::GameRecord;
+use crate::rng::Rng;
+use crate::scoreboard::Scoreboard;
+use crate::strategy::Strategy;
 use std::fmt;
 
-/// Board size constant
-const BOARD_SIZE: usize = 3;
-
-/// Represents the two players in the game
+/// Represents who controls a side of the game: a human entering moves or the AI choosing them
+/// Both sides may have the same controller, depending on the `GameMode`
 #[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub enum Player {
     Human,
     Ai,
 }
 
+impl Player {
+    /// Returns the other controller
+    pub fn other(self) -> Player {
+        match self {
+            Player::Human => Player::Ai,
+            Player::Ai => Player::Human,
+        }
+    }
+}
+
+/// Which controllers play the two sides
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub enum GameMode {
+    /// One side is entered by a human and the other played by the AI
+    #[default]
+    HumanVsAi,
+    /// Both sides are entered by humans sharing the game
+    HumanVsHuman,
+    /// The AI plays both sides
+    AiVsAi,
+}
+
+impl GameMode {
+    /// Parses a mode from its variant name, as written in transcripts
+    fn from_name(name: &str) -> Option<GameMode> {
+        match name {
+            "HumanVsAi" => Some(GameMode::HumanVsAi),
+            "HumanVsHuman" => Some(GameMode::HumanVsHuman),
+            "AiVsAi" => Some(GameMode::AiVsAi),
+            _ => None,
+        }
+    }
+}
+
+/// Mode-neutral result of a finished game, naming the winning mark
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Outcome {
+    Win(Cell),
+    Draw,
+}
+
 /// Represents the possible game outcomes
 #[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub enum GameResult {
     HumanWin,
     AiWin,
     Draw,
 }
 
+impl GameResult {
+    /// Returns the reward for `player`: 1 for a win, 0 for a draw, -1 for a loss
+    pub fn reward_for(&self, player: Player) -> f32 {
+        match (self, player) {
+            (GameResult::Draw, _) => 0.0,
+            (GameResult::HumanWin, Player::Human) | (GameResult::AiWin, Player::Ai) => 1.0,
+            (GameResult::HumanWin, Player::Ai) | (GameResult::AiWin, Player::Human) => -1.0,
+        }
+    }
+}
+
+/// What perfect play from both sides leads to, from the point of view of the side to move
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Prognosis {
+    /// The side to move can force a win
+    WinForCurrent,
+    /// The opponent can force a win whatever the side to move does
+    LossForCurrent,
+    /// Neither side can force a win
+    Draw,
+}
+
 /// Represents errors that can occur during gameplay
 #[derive(Debug, Clone, PartialEq, Eq)]
 pub enum GameError {
@@ -29,41 +98,298 @@ pub enum GameError {
     PositionOccupied,
     GameOver,
     WrongPlayer,
+    InvalidPly,
+    IllegalState,
+    NothingToUndo,
+    NothingToRedo,
+    InvalidSnapshot,
+    /// The AI found no move in a game that isn't over; only a custom `Strategy` can do this
+    AiNoMove,
+    InvalidTranscript,
+    /// A `GameBuilder` was given settings that can't work together
+    InvalidConfiguration,
+    /// `accept_draw` was called with no draw offer pending
+    NoDrawOffer,
 }
 
 impl fmt::Display for GameError {
     fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
         match self {
-            GameError::InvalidPosition => write!(f, "Invalid position (must be 0-2)"),
+            GameError::InvalidPosition => write!(f, "Position is off the board"),
             GameError::PositionOccupied => write!(f, "Position is already occupied"),
             GameError::GameOver => write!(f, "Game is already over"),
             GameError::WrongPlayer => write!(f, "Not your turn"),
+            GameError::InvalidPly => write!(f, "No such move in the game history"),
+            GameError::IllegalState => write!(f, "Board state can't arise from legal play"),
+            GameError::NothingToUndo => write!(f, "No moves to undo"),
+            GameError::NothingToRedo => write!(f, "No moves to redo"),
+            GameError::InvalidSnapshot => write!(f, "Saved game is malformed or inconsistent"),
+            GameError::AiNoMove => write!(f, "AI found no move in an unfinished game"),
+            GameError::InvalidTranscript => write!(f, "Transcript is malformed or illegal"),
+            GameError::InvalidConfiguration => write!(f, "Game settings are inconsistent"),
+            GameError::NoDrawOffer => write!(f, "No draw has been offered"),
         }
     }
 }
 
 impl std::error::Error for GameError {}
 
+/// Everything a UI needs to know after a move: what was placed where and the resulting state
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct MoveOutcome {
+    /// The mark that was placed
+    pub placed: Cell,
+    /// The position it was placed at
+    pub at: (usize, usize),
+    /// The result if the move ended the game
+    pub result: Option<GameResult>,
+    /// The player to move next; once the game is over this is whoever moved last
+    pub next_player: Player,
+}
+
+/// A snapshot of the whole game state, for logging and headless runs
+/// Created by `Game::status`
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct GameStatus {
+    pub board: Board,
+    /// The player to move; once the game is over this is whoever moved last
+    pub to_move: Player,
+    /// The result once the game is over
+    pub result: Option<GameResult>,
+    /// Number of moves played so far
+    pub move_number: usize,
+}
+
+/// The saved state of a game: the board, the mark to move, the mode and settings,
+/// and every move played
+/// Created by `Game::snapshot` and restored by `Game::from_snapshot`
+#[cfg(feature = "serde")]
+#[derive(Debug, Clone, PartialEq, Eq, serde::Serialize, serde::Deserialize)]
+pub struct GameSnapshot {
+    pub board: Board,
+    pub to_move: Cell,
+    pub mode: GameMode,
+    pub human_mark: Cell,
+    pub first_mark: Cell,
+    pub one_based: bool,
+    pub gravity: bool,
+    pub early_draw: bool,
+    pub rules: RuleSet,
+    /// Every move played, as (row, col, mark)
+    pub moves: Vec<(usize, usize, Cell)>,
+    /// The mark that resigned, if the game ended that way
+    pub resigned: Option<Cell>,
+    pub agreed_draw: bool,
+}
+
+/// Steps through a game's moves, yielding the board after each one
+/// Created by `Game::replay`
+pub struct Replay {
+    board: Board,
+    moves: std::vec::IntoIter<(usize, usize, Cell)>,
+}
+
+impl Iterator for Replay {
+    type Item = Board;
+
+    fn next(&mut self) -> Option<Board> {
+        let (row, col, cell) = self.moves.next()?;
+        self.board.set(row, col, cell);
+        Some(self.board.clone())
+    }
+}
+
+/// A reviewed move: its ply index, its quality, and a better move when one existed
+pub type ReviewEntry = (usize, MoveQuality, Option<(usize, usize)>);
+
+/// A callback told about each move: who played it, where, and which mark was placed
+pub type MoveListener = Box<dyn FnMut(Player, usize, usize, Cell)>;
+
 /// Main game controller that manages the tic-tac-toe game
 pub struct Game {
     board: Board,
-    current_player: Player,
+    mode: GameMode,
+    /// The mark placed by the next move
+    to_move: Cell,
+    /// The mark that opens the game
+    first_mark: Cell,
     ai_agent: AiAgent,
+    /// Engine choosing the AI's moves in place of `ai_agent`, if one was given
+    strategy: Option<Box<dyn Strategy>>,
+    /// The human's mark in `GameMode::HumanVsAi`
+    human_mark: Cell,
+    history: Vec<(usize, usize, Cell)>,
+    /// Moves taken back by `undo`, most recent last
+    redo_stack: Vec<(usize, usize, Cell)>,
+    one_based: bool,
+    /// Whether marks drop to the lowest empty row of their column
+    gravity: bool,
+    /// Whether the game ends in a draw as soon as no line can be completed
+    early_draw: bool,
+    rules: RuleSet,
+    /// The mark whose side resigned, ending the game
+    resigned: Option<Cell>,
+    /// Whether a draw has been offered and not yet accepted or passed over by a move
+    draw_offered: bool,
+    /// Whether the players agreed to a draw, ending the game
+    agreed_draw: bool,
+    rejected_human_moves: usize,
+    rejected_ai_moves: usize,
+    /// Results of finished games, tallied as they are reset for a rematch
+    scoreboard: Option<Scoreboard>,
+    /// Callbacks registered with `on_move`, called in registration order
+    move_listeners: Vec<MoveListener>,
 }
 
 impl Game {
-    /// Creates a new game with the human player going first
+    /// Creates a new game with the human player going first as X
     pub fn new() -> Self {
+        Self::with_options(Cell::X, true)
+    }
+
+    /// Creates a new game where the human plays `human_mark` and the AI the other mark
+    /// The human moves first if `human_first` is set; `Cell::Empty` is treated as X
+    pub fn with_options(human_mark: Cell, human_first: bool) -> Self {
+        let human_mark = if human_mark == Cell::O {
+            Cell::O
+        } else {
+            Cell::X
+        };
+        let first_mark = if human_first {
+            human_mark
+        } else {
+            human_mark.opponent()
+        };
+        Self::build(GameMode::HumanVsAi, human_mark, first_mark)
+    }
+
+    /// Creates a new game where the human plays X and `seed` decides who moves first,
+    /// so a series of games can share the first move fairly yet be replayed exactly
+    /// When the AI is first, nothing is played until `make_ai_move` is called
+    pub fn with_random_first(seed: u64) -> Self {
+        Self::with_options(Cell::X, Rng::new(seed).below(2) == 0)
+    }
+
+    /// Creates a new game like `Game::new` whose AI moves are chosen by `strategy`
+    /// instead of the built-in minimax agent; hints still come from the agent
+    pub fn with_strategy(strategy: Box<dyn Strategy>) -> Self {
+        let mut game = Self::new();
+        game.set_strategy(strategy);
+        game
+    }
+
+    /// Hands the choice of the AI's moves to `strategy`
+    /// Moves it chooses are checked like human moves, so an illegal one is rejected
+    pub fn set_strategy(&mut self, strategy: Box<dyn Strategy>) {
+        self.strategy = Some(strategy);
+    }
+
+    /// Returns a builder for configuring every setting of a new game
+    pub fn builder() -> GameBuilder {
+        GameBuilder::new()
+    }
+
+    /// Creates a new game in which `mode` decides who controls each side, X moving first
+    /// In `GameMode::HumanVsAi` the human plays X
+    pub fn with_mode(mode: GameMode) -> Self {
+        Self::build(mode, Cell::X, Cell::X)
+    }
+
+    /// Rebuilds a game in `mode` by playing `moves` in order, X moving first
+    /// Fails with the error of the first move that can't be played
+    pub fn from_moves(moves: &[(usize, usize)], mode: GameMode) -> Result<Game, GameError> {
+        let mut game = Self::with_mode(mode);
+        for &(row, col) in moves {
+            game.try_place(row, col)?;
+        }
+        Ok(game)
+    }
+
+    /// Plays a full game between two independent agents, `agent_x` moving first,
+    /// and returns the outcome along with every move played
+    pub fn self_play(agent_x: AiAgent, agent_o: AiAgent) -> (Outcome, GameRecord) {
+        let mut game = Self::with_mode(GameMode::AiVsAi);
+        while game.expecting().is_some() {
+            let mark = game.to_move;
+            let agent = if mark == Cell::X { &agent_x } else { &agent_o };
+            match agent.get_best_move_for(&game.board, mark) {
+                Some((row, col)) => game.play_move(row, col, mark),
+                None => break,
+            }
+        }
+
+        // The loop only ends once the board is won or full
+        let outcome = game.outcome().unwrap_or(Outcome::Draw);
+        (outcome, game.record())
+    }
+
+    /// Creates an empty game from its settings
+    fn build(mode: GameMode, human_mark: Cell, first_mark: Cell) -> Self {
         Self {
             board: Board::new(),
-            current_player: Player::Human,
+            mode,
+            to_move: first_mark,
+            first_mark,
             ai_agent: AiAgent::new(),
+            strategy: None,
+            human_mark,
+            history: Vec::new(),
+            redo_stack: Vec::new(),
+            one_based: false,
+            gravity: false,
+            early_draw: false,
+            rules: RuleSet::Standard,
+            resigned: None,
+            draw_offered: false,
+            agreed_draw: false,
+            rejected_human_moves: 0,
+            rejected_ai_moves: 0,
+            scoreboard: None,
+            move_listeners: Vec::new(),
         }
     }
 
     /// Returns the current player
     pub fn current_player(&self) -> Player {
-        self.current_player
+        self.controller_of(self.to_move)
+    }
+
+    /// Returns the mark `player` places: the configured human mark or the other one in
+    /// `GameMode::HumanVsAi`, the mark to move when `player` controls both sides, and
+    /// `Cell::Empty` when `player` controls neither
+    pub fn mark_of(&self, player: Player) -> Cell {
+        match (self.mode, player) {
+            (GameMode::HumanVsAi, Player::Human) => self.human_mark,
+            (GameMode::HumanVsAi, Player::Ai) => self.human_mark.opponent(),
+            (GameMode::HumanVsHuman, Player::Human) | (GameMode::AiVsAi, Player::Ai) => {
+                self.to_move
+            }
+            (GameMode::HumanVsHuman, Player::Ai) | (GameMode::AiVsAi, Player::Human) => Cell::Empty,
+        }
+    }
+
+    /// Returns who places `cell`, or None for `Cell::Empty`
+    pub fn player_of(&self, cell: Cell) -> Option<Player> {
+        (cell != Cell::Empty).then(|| self.controller_of(cell))
+    }
+
+    /// Returns who controls each side of the game
+    pub fn mode(&self) -> GameMode {
+        self.mode
+    }
+
+    /// Returns the player expected to submit the next move, or None once the game is over
+    pub fn expecting(&self) -> Option<Player> {
+        if self.is_over() {
+            None
+        } else {
+            Some(self.current_player())
+        }
+    }
+
+    /// Returns true once the board is won or full, a side has resigned, or a draw was agreed
+    fn is_over(&self) -> bool {
+        self.outcome().is_some()
     }
 
     /// Returns a reference to the current board
@@ -71,25 +397,295 @@ impl Game {
         &self.board
     }
 
+    /// Returns every position the side to move may play, or nothing once the game is over
+    /// With gravity, only the landing cell of each column that isn't full
+    pub fn available_moves(&self) -> Vec<(usize, usize)> {
+        if self.is_over() {
+            Vec::new()
+        } else if self.gravity {
+            self.board.landing_positions()
+        } else {
+            self.board.empty_positions()
+        }
+    }
+
+    /// Returns true if the side to move may play at `(row, col)`: the game isn't over
+    /// and the position is an empty cell on the board (the lowest one in its column, with gravity)
+    pub fn is_legal_move(&self, row: usize, col: usize) -> bool {
+        !self.is_over()
+            && self.board.get(row, col) == Some(Cell::Empty)
+            && (!self.gravity || self.board.landing_row(col) == Some(row))
+    }
+
+    /// Returns the board after every move played so far, starting with the empty board
+    pub fn positions(&self) -> Vec<Board> {
+        let mut board = self.empty_board();
+        let mut positions = vec![board.clone()];
+        for &(row, col, cell) in &self.history {
+            board.set(row, col, cell);
+            positions.push(board.clone());
+        }
+        positions
+    }
+
+    /// Returns a human move that would let the AI answer with a double threat
+    /// the human cannot meet, or None if no such blunder is available right now
+    /// Only meaningful with a single human, so other modes always return None
+    pub fn human_about_to_be_forked(&self) -> Option<(usize, usize)> {
+        if self.mode != GameMode::HumanVsAi || self.expecting() != Some(Player::Human) {
+            return None;
+        }
+
+        self.board
+            .empty_positions()
+            .into_iter()
+            .find(|&(row, col)| {
+                let human_mark = self.human_mark;
+                let ai_mark = human_mark.opponent();
+                let mut after_human = self.board.clone();
+                after_human.set(row, col, human_mark);
+                // Leaving an immediate win open is a plain blunder, not a fork
+                if after_human.is_game_over() || !after_human.winning_moves_for(ai_mark).is_empty()
+                {
+                    return false;
+                }
+
+                after_human
+                    .empty_positions()
+                    .into_iter()
+                    .any(|(ai_row, ai_col)| {
+                        let mut after_ai = after_human.clone();
+                        after_ai.set(ai_row, ai_col, ai_mark);
+                        !after_ai.is_game_over()
+                            && after_ai.winning_moves_for(ai_mark).len() >= 2
+                            && after_ai.winning_moves_for(human_mark).is_empty()
+                    })
+            })
+    }
+
+    /// Suggests the strongest move for the human to move, searching for their mark
+    /// Returns None once the game is over or while the AI is to move
+    pub fn hint(&self) -> Option<(usize, usize)> {
+        if self.expecting() != Some(Player::Human) {
+            return None;
+        }
+        if self.gravity {
+            self.ai_agent.optimal_drop(&self.board, self.to_move)
+        } else {
+            self.ai_agent.optimal_move(&self.board, self.to_move)
+        }
+    }
+
+    /// Classifies the current position assuming both sides play perfectly from here
+    /// A finished game counts as a loss for the side to move if the other side won
+    pub fn outcome_with_perfect_play(&self) -> Prognosis {
+        // The turn doesn't pass once the game ends, so look at who made the last move
+        let to_move = match self.history.last() {
+            Some(&(_, _, last_mark)) if self.board.is_game_over() => last_mark.opponent(),
+            _ => self.to_move,
+        };
+        match self.ai_agent.evaluate(&self.board, to_move).signum() {
+            1 => Prognosis::WinForCurrent,
+            -1 => Prognosis::LossForCurrent,
+            _ => Prognosis::Draw,
+        }
+    }
+
+    /// Registers a callback run after every newly played move, by the human or the AI
+    /// Moves replayed by `redo`, `rewind_to` or loading a game aren't reported
+    pub fn on_move(&mut self, listener: MoveListener) {
+        self.move_listeners.push(listener);
+    }
+
+    /// Starts keeping a scoreboard that tallies each finished game when `reset` starts a rematch
+    /// Keeps the existing tally if one is already kept
+    pub fn track_score(&mut self) {
+        self.scoreboard.get_or_insert_with(Scoreboard::new);
+    }
+
+    /// Returns the scoreboard, or None if `track_score` was never called
+    pub fn scoreboard(&self) -> Option<&Scoreboard> {
+        self.scoreboard.as_ref()
+    }
+
+    /// Returns an iterator over the board after each move played so far, for stepping
+    /// through a game; unlike `positions` the empty starting board isn't included
+    pub fn replay(&self) -> Replay {
+        Replay {
+            board: self.empty_board(),
+            moves: self.history.clone().into_iter(),
+        }
+    }
+
+    /// Returns a record of every move played so far
+    pub fn record(&self) -> GameRecord {
+        GameRecord::from_moves(self.history.clone())
+    }
+
+    /// Reviews every human move played so far
+    pub fn postgame_review(&self) -> Vec<ReviewEntry> {
+        let positions = self.positions();
+        let mut review = Vec::new();
+
+        for (ply, &(row, col, cell)) in self.history.iter().enumerate() {
+            if self.controller_of(cell) != Player::Human {
+                continue;
+            }
+
+            let before = &positions[ply];
+            let quality = match self.ai_agent.classify_move(before, cell, (row, col)) {
+                Some(quality) => quality,
+                None => continue,
+            };
+            let better = if quality == MoveQuality::Best {
+                None
+            } else {
+                self.ai_agent.best_moves(before, cell).first().copied()
+            };
+
+            review.push((ply, quality, better));
+        }
+
+        review
+    }
+
+    /// Sets whether coordinates are shown and parsed starting from 1 instead of 0
+    /// Moves are always stored 0-based internally
+    pub fn set_one_based(&mut self, one_based: bool) {
+        self.one_based = one_based;
+    }
+
+    /// Returns true if coordinates are shown and parsed starting from 1
+    pub fn is_one_based(&self) -> bool {
+        self.one_based
+    }
+
+    /// Turns the gravity variant on or off: marks drop to the lowest empty row of the chosen
+    /// column, and moves are entered as a column
+    pub fn set_gravity(&mut self, gravity: bool) {
+        self.gravity = gravity;
+    }
+
+    /// Returns true if marks drop to the lowest empty row of their column
+    pub fn has_gravity(&self) -> bool {
+        self.gravity
+    }
+
+    /// Sets whether the game is drawn as soon as neither side can complete a line,
+    /// rather than only once the board is full
+    pub fn set_early_draw(&mut self, early_draw: bool) {
+        self.early_draw = early_draw;
+    }
+
+    /// Returns true if the game is drawn as soon as no line can be completed
+    pub fn has_early_draw(&self) -> bool {
+        self.early_draw
+    }
+
+    /// Changes whether completing a line wins or loses, for the game and its AI
+    pub fn set_rules(&mut self, rules: RuleSet) {
+        self.rules = rules;
+        self.ai_agent.set_rules(rules);
+    }
+
+    /// Returns whether completing a line wins or loses
+    pub fn rules(&self) -> RuleSet {
+        self.rules
+    }
+
+    /// Parses a "row col" input into 0-based board coordinates, honoring the one-based setting
+    /// With gravity the input is a single column, and the row is where the mark would land
+    /// Returns None if the input isn't in-range numbers, or names a full column
+    pub fn parse_move(&self, input: &str) -> Option<(usize, usize)> {
+        let origin = if self.one_based { 1 } else { 0 };
+        let parts: Vec<&str> = input.split_whitespace().collect();
+        if self.gravity {
+            let [col] = parts[..] else {
+                return None;
+            };
+            let col = col.parse::<usize>().ok()?.checked_sub(origin)?;
+            return Some((self.board.landing_row(col)?, col));
+        }
+        if parts.len() != 2 {
+            return None;
+        }
+
+        let row = parts[0].parse::<usize>().ok()?.checked_sub(origin)?;
+        let col = parts[1].parse::<usize>().ok()?.checked_sub(origin)?;
+        if row < self.board.size() && col < self.board.size() {
+            Some((row, col))
+        } else {
+            None
+        }
+    }
+
+    /// Returns the current board as text, labeled the way the game reads coordinates
+    pub fn render_board(&self) -> String {
+        self.board.render_with_origin(self.one_based)
+    }
+
     /// Displays the current board state
     pub fn display_board(&self) {
-        println!("{}", self.board);
+        println!("{}", self.render_board());
     }
 
     /// Makes a move for the human player
     pub fn make_human_move(&mut self, row: usize, col: usize) -> Result<(), GameError> {
+        let result = self.try_human_move(row, col);
+        self.track_rejection(Player::Human, &result);
+        result
+    }
+
+    /// Makes a move for the human player like `make_human_move`, reporting the move
+    /// and the state it led to
+    pub fn make_human_move_detailed(
+        &mut self,
+        row: usize,
+        col: usize,
+    ) -> Result<MoveOutcome, GameError> {
+        let placed = self.to_move;
+        self.make_human_move(row, col)?;
+        Ok(MoveOutcome {
+            placed,
+            at: (row, col),
+            result: self.check_game_over(),
+            next_player: self.current_player(),
+        })
+    }
+
+    /// Validates and plays a human move
+    fn try_human_move(&mut self, row: usize, col: usize) -> Result<(), GameError> {
         // Check if game is over
-        if self.board.is_game_over() {
+        if self.is_over() {
             return Err(GameError::GameOver);
         }
 
         // Check if it's the human player's turn
-        if self.current_player != Player::Human {
+        if self.mark_of(Player::Human) != self.to_move {
             return Err(GameError::WrongPlayer);
         }
 
+        self.try_place(row, col)
+    }
+
+    /// Places the mark of the side to move, whoever controls it, and passes the turn
+    /// This is how both sides enter moves in `GameMode::HumanVsHuman`
+    pub fn make_move(&mut self, row: usize, col: usize) -> Result<(), GameError> {
+        let player = self.current_player();
+        let result = self.try_place(row, col);
+        self.track_rejection(player, &result);
+        result
+    }
+
+    /// Validates the position and plays the mark of the side to move there
+    fn try_place(&mut self, row: usize, col: usize) -> Result<(), GameError> {
+        // Check if game is over
+        if self.is_over() {
+            return Err(GameError::GameOver);
+        }
+
         // Validate position
-        if row >= BOARD_SIZE || col >= BOARD_SIZE {
+        if self.board.get(row, col).is_none() {
             return Err(GameError::InvalidPosition);
         }
 
@@ -98,132 +694,734 @@ impl Game {
             return Err(GameError::PositionOccupied);
         }
 
+        // With gravity, marks can only be placed where they would land
+        if self.gravity && self.board.landing_row(col) != Some(row) {
+            return Err(GameError::InvalidPosition);
+        }
+
         // Make the move
-        self.board.set(row, col, Cell::X);
+        self.play_move(row, col, self.to_move);
 
-        // Switch to AI player if game is not over
-        if !self.board.is_game_over() {
-            self.current_player = Player::Ai;
-        }
+        Ok(())
+    }
+
+    /// Drops the mark of the side to move, whoever controls it, into `col` and returns the row
+    /// it landed on; used with gravity, though it also works without
+    pub fn drop_move(&mut self, col: usize) -> Result<usize, GameError> {
+        // A full column falls back to its top cell, which `make_move` rejects as occupied
+        let row = self.board.landing_row(col).unwrap_or(0);
+        self.make_move(row, col).map(|()| row)
+    }
 
+    /// Plays `moves` in order, each for the side to move, stopping at the first one that
+    /// can't be played and reporting its index with the error
+    /// In `GameMode::HumanVsAi` the AI takes its turns before each supplied move, so the
+    /// moves are the human's; it doesn't reply to the last one
+    pub fn apply_moves(&mut self, moves: &[(usize, usize)]) -> Result<(), (usize, GameError)> {
+        for (index, &(row, col)) in moves.iter().enumerate() {
+            if self.mode == GameMode::HumanVsAi {
+                while self.expecting() == Some(Player::Ai) {
+                    self.make_ai_move().map_err(|error| (index, error))?;
+                }
+            }
+            self.make_move(row, col).map_err(|error| (index, error))?;
+        }
         Ok(())
     }
 
+    /// Makes a move for the human player from a `Move` or a `(row, col)` tuple
+    pub fn make_human_move_at(&mut self, mv: impl Into<Move>) -> Result<(), GameError> {
+        let mv = mv.into();
+        self.make_human_move(mv.row, mv.col)
+    }
+
     /// Makes a move for the AI player
     pub fn make_ai_move(&mut self) -> Result<(), GameError> {
+        let result = self.try_ai_move();
+        self.track_rejection(Player::Ai, &result);
+        result
+    }
+
+    /// Validates and plays the AI's move
+    fn try_ai_move(&mut self) -> Result<(), GameError> {
         // Check if game is over
-        if self.board.is_game_over() {
+        if self.is_over() {
             return Err(GameError::GameOver);
         }
 
         // Check if it's the AI player's turn
-        if self.current_player != Player::Ai {
+        let mark = self.mark_of(Player::Ai);
+        if mark != self.to_move {
             return Err(GameError::WrongPlayer);
         }
 
         // Get the best move from the AI
-        if let Some((row, col)) = self.ai_agent.get_best_move(&self.board) {
-            self.board.set(row, col, Cell::O);
+        // The built-in AI always finds a move in an unfinished game, but a custom strategy
+        // may return none, or an illegal move that `try_place` rejects
+        if let Some((row, col)) = self.ai_choice(mark) {
+            self.try_place(row, col)
+        } else {
+            Err(GameError::AiNoMove)
+        }
+    }
 
-            // Switch to human player if game is not over
-            if !self.board.is_game_over() {
-                self.current_player = Player::Human;
-            }
+    /// Plays the AI's choice of move for whichever player is to move, human included
+    /// Returns the position that was played
+    pub fn auto_move(&mut self) -> Result<(usize, usize), GameError> {
+        // Check if game is over
+        if self.is_over() {
+            return Err(GameError::GameOver);
+        }
 
-            Ok(())
+        let mark = self.to_move;
+        // As in `try_ai_move`, only a custom strategy can come up empty or choose badly
+        if let Some((row, col)) = self.ai_choice(mark) {
+            self.try_place(row, col).map(|()| (row, col))
         } else {
-            // This should not happen if the game logic is correct
-            Err(GameError::GameOver)
+            Err(GameError::AiNoMove)
         }
     }
 
-    /// Checks if the game is over and returns the result
-    pub fn check_game_over(&self) -> Option<GameResult> {
-        if let Some(winner) = self.board.check_winner() {
-            match winner {
-                Cell::X => Some(GameResult::HumanWin),
-                Cell::O => Some(GameResult::AiWin),
-                Cell::Empty => None, // This should never happen
-            }
-        } else if self.board.is_full() {
-            Some(GameResult::Draw)
+    /// Returns the custom strategy's move for `mark` if one is set, otherwise the agent's,
+    /// dropping into a column when gravity is on
+    fn ai_choice(&mut self, mark: Cell) -> Option<(usize, usize)> {
+        if let Some(strategy) = &mut self.strategy {
+            strategy.choose_move(&self.board, mark)
+        } else if self.gravity {
+            self.ai_agent.get_best_drop_for(&self.board, mark)
         } else {
-            None
+            self.ai_agent.get_best_move_for(&self.board, mark)
         }
     }
 
-    /// Resets the game to initial state
-    pub fn reset(&mut self) {
-        self.board = Board::new();
-        self.current_player = Player::Human;
+    /// Returns how many move attempts by `player` were rejected as out of bounds,
+    /// on an occupied cell, or out of turn
+    pub fn rejected_moves(&self, player: Player) -> usize {
+        match player {
+            Player::Human => self.rejected_human_moves,
+            Player::Ai => self.rejected_ai_moves,
+        }
     }
-}
 
-impl Default for Game {
-    fn default() -> Self {
-        Self::new()
+    /// Counts a rejected move attempt against `player` if `result` is a rule violation
+    fn track_rejection(&mut self, player: Player, result: &Result<(), GameError>) {
+        if matches!(
+            result,
+            Err(GameError::InvalidPosition | GameError::PositionOccupied | GameError::WrongPlayer)
+        ) {
+            match player {
+                Player::Human => self.rejected_human_moves += 1,
+                Player::Ai => self.rejected_ai_moves += 1,
+            }
+        }
     }
-}
 
-#[cfg(test)]
-mod tests {
-    use super::*;
+    /// Rewinds the game to the position after `ply` moves, discarding later moves
+    /// Play can resume from there with the correct player to move
+    pub fn rewind_to(&mut self, ply: usize) -> Result<(), GameError> {
+        if ply > self.history.len() {
+            return Err(GameError::InvalidPly);
+        }
 
-    #[test]
-    fn test_new_game() {
-        let game = Game::new();
-        assert_eq!(game.current_player(), Player::Human);
-        assert!(game.check_game_over().is_none());
+        self.history.truncate(ply);
+        self.redo_stack.clear();
+        self.rebuild_from_history();
+        Ok(())
     }
 
-    #[test]
-    fn test_human_move() {
-        let mut game = Game::new();
-        assert!(game.make_human_move(1, 1).is_ok());
-        assert_eq!(game.current_player(), Player::Ai);
-        assert_eq!(game.board().get(1, 1), Some(Cell::X));
+    /// Takes back the last move, restoring the player who made it and reopening a finished game
+    pub fn undo(&mut self) -> Result<(), GameError> {
+        let last_move = self.history.pop().ok_or(GameError::NothingToUndo)?;
+        self.redo_stack.push(last_move);
+        self.rebuild_from_history();
+        Ok(())
     }
 
-    #[test]
-    fn test_invalid_moves() {
-        let mut game = Game::new();
-
-        // Test invalid position
-        assert_eq!(game.make_human_move(3, 3), Err(GameError::InvalidPosition));
+    /// Replays the move most recently taken back by `undo`
+    pub fn redo(&mut self) -> Result<(), GameError> {
+        let (row, col, cell) = self.redo_stack.pop().ok_or(GameError::NothingToRedo)?;
+        self.apply_move(row, col, cell);
+        Ok(())
+    }
 
-        // Test occupied position
-        game.make_human_move(0, 0).unwrap();
-        game.make_ai_move().unwrap(); // Switch turns
-        assert_eq!(game.make_human_move(0, 0), Err(GameError::PositionOccupied));
+    /// Takes back moves until it's the human's turn again, so after an AI reply
+    /// both the AI's move and the human's move before it are reverted
+    pub fn undo_full_turn(&mut self) -> Result<(), GameError> {
+        self.undo()?;
+        while self.current_player() != Player::Human && !self.history.is_empty() {
+            self.undo()?;
+        }
+        Ok(())
     }
 
-    #[test]
-    fn test_wrong_player_errors() {
-        let mut game = Game::new();
+    /// Returns the number of moves played so far
+    pub fn move_count(&self) -> usize {
+        self.history.len()
+    }
 
-        // Test human trying to move when it's AI's turn
-        game.make_human_move(1, 1).unwrap(); // Human moves first
-        assert_eq!(game.current_player(), Player::Ai);
-        assert_eq!(game.make_human_move(0, 0), Err(GameError::WrongPlayer));
+    /// Returns an empty board with the same size and win length as the game's board
+    fn empty_board(&self) -> Board {
+        self.board.cleared()
+    }
 
-        // Test AI trying to move when it's human's turn
-        game.make_ai_move().unwrap(); // AI moves
-        assert_eq!(game.current_player(), Player::Human);
-        assert_eq!(game.make_ai_move(), Err(GameError::WrongPlayer));
+    /// Replays the recorded history from an empty board to restore the board and turn
+    fn rebuild_from_history(&mut self) {
+        let history = std::mem::take(&mut self.history);
+        self.board = self.empty_board();
+        self.to_move = self.first_mark;
+        self.resigned = None;
+        self.draw_offered = false;
+        self.agreed_draw = false;
+        for (row, col, cell) in history {
+            self.apply_move(row, col, cell);
+        }
     }
 
-    #[test]
-    fn test_game_over_scenarios() {
-        // Manually set up a winning condition by directly manipulating the board
-        // This bypasses the AI logic and ensures we can test game over behavior
-        let mut board = Board::new();
-        board.set(0, 0, Cell::X);
-        board.set(0, 1, Cell::X);
-        board.set(0, 2, Cell::X); // X wins
+    /// Plays a new move, abandoning any moves that could have been redone
+    /// A pending draw offer lapses once the move is made
+    fn play_move(&mut self, row: usize, col: usize, cell: Cell) {
+        self.redo_stack.clear();
+        self.draw_offered = false;
+        self.apply_move(row, col, cell);
 
-        // Create a new game with this winning board
-        let mut winning_game = Game::new();
-        winning_game.board = board;
+        let player = self.controller_of(cell);
+        for listener in &mut self.move_listeners {
+            listener(player, row, col, cell);
+        }
+    }
+
+    /// Places `cell` on the board, records it, and passes the turn unless the game ended
+    fn apply_move(&mut self, row: usize, col: usize, cell: Cell) {
+        self.board.set(row, col, cell);
+        self.history.push((row, col, cell));
+
+        // Switch players if game is not over; only lines through the new mark can have
+        // been completed, which keeps this cheap on large boards
+        if self.board.check_winner_near((row, col)).is_none() && !self.board.is_full() {
+            self.to_move = self.to_move.opponent();
+        }
+    }
+
+    /// Writes the game as a transcript: a header line naming the mode, board and win length
+    /// (plus the human's mark when playing the AI, and `misere` or `gravity` when those
+    /// variants are on), such as `HumanVsAi, 3x3, 3 in a row, human X, misere`, then the
+    /// moves numbered one line per turn pair, such as `1. X b2  O a1`
+    /// Columns are letters from `a`, continuing `aa`, `ab`... past `z`, and rows numbers
+    /// from 1, so `a1` is the top-left cell
+    pub fn transcript(&self) -> String {
+        let mut header = format!(
+            "{:?}, {}x{}, {} in a row",
+            self.mode,
+            self.board.size(),
+            self.board.size(),
+            self.board.win_length()
+        );
+        if self.mode == GameMode::HumanVsAi {
+            header.push_str(&format!(", human {}", self.human_mark));
+        }
+        if self.rules == RuleSet::Misere {
+            header.push_str(", misere");
+        }
+        if self.gravity {
+            header.push_str(", gravity");
+        }
+
+        let turns = self.history.chunks(2).enumerate().map(|(turn, pair)| {
+            let moves: Vec<String> = pair
+                .iter()
+                .map(|&(row, col, cell)| format!("{} {}{}", cell, column_letters(col), row + 1))
+                .collect();
+            format!("{}. {}", turn + 1, moves.join("  "))
+        });
+        core::iter::once(header)
+            .chain(turns)
+            .collect::<Vec<_>>()
+            .join("\n")
+    }
+
+    /// Rebuilds a game from a `transcript`, with the mode, board, human mark and variants it names
+    /// Transcripts without a header line are 3x3 games in the default mode with the
+    /// human as X; turn numbers are skipped, the marks must alternate and every move must
+    /// be legal
+    pub fn from_transcript(input: &str) -> Result<Game, GameError> {
+        let input = input.trim_start();
+        // Move lines start with a turn number, so a line starting with a letter is a header
+        let (header, moves) = if input.starts_with(char::is_alphabetic) {
+            let (header, moves) = input.split_once('\n').unwrap_or((input, ""));
+            let header =
+                Self::parse_transcript_header(header).ok_or(GameError::InvalidTranscript)?;
+            (header, moves)
+        } else {
+            (TranscriptHeader::default(), input)
+        };
+
+        let mut tokens = moves
+            .split_whitespace()
+            .filter(|token| !token.ends_with('.'))
+            .peekable();
+        let first_mark = match tokens.peek() {
+            Some(&mark) => mark.parse().map_err(|_| GameError::InvalidTranscript)?,
+            None => Cell::X,
+        };
+        let mut game = Self::build(header.mode, header.human_mark, first_mark);
+        game.board = header.board;
+        game.set_rules(header.rules);
+        game.gravity = header.gravity;
+
+        while let Some(mark) = tokens.next() {
+            let mark: Cell = mark.parse().map_err(|_| GameError::InvalidTranscript)?;
+            let square = tokens.next().ok_or(GameError::InvalidTranscript)?;
+            let digits = square.trim_start_matches(|c: char| c.is_ascii_lowercase());
+            let col = parse_column_letters(&square[..square.len() - digits.len()]);
+            let row = digits
+                .parse::<usize>()
+                .ok()
+                .and_then(|row| row.checked_sub(1));
+            let (Some(row), Some(col)) = (row, col) else {
+                return Err(GameError::InvalidTranscript);
+            };
+            if mark != game.to_move || game.try_place(row, col).is_err() {
+                return Err(GameError::InvalidTranscript);
+            }
+        }
+        Ok(game)
+    }
+
+    /// Reads the mode, empty board and human mark from a transcript header line
+    fn parse_transcript_header(header: &str) -> Option<TranscriptHeader> {
+        let mut fields = header.trim().split(", ");
+        let mode = GameMode::from_name(fields.next()?)?;
+        let (rows, cols) = fields.next()?.split_once('x')?;
+        let size: usize = rows.parse().ok()?;
+        if cols.parse::<usize>().ok()? != size {
+            return None;
+        }
+        let win_length = fields.next()?.strip_suffix(" in a row")?.parse().ok()?;
+        let board = Board::with_win_length(size, win_length).ok()?;
+        let mut fields = fields.peekable();
+        let human_mark = match fields.next_if(|field| field.starts_with("human ")) {
+            Some(field) => field["human ".len()..].parse().ok()?,
+            None => Cell::X,
+        };
+        let rules = match fields.next_if_eq(&"misere") {
+            Some(_) => RuleSet::Misere,
+            None => RuleSet::Standard,
+        };
+        let gravity = fields.next_if_eq(&"gravity").is_some();
+        if human_mark == Cell::Empty || fields.next().is_some() {
+            return None;
+        }
+        Some(TranscriptHeader {
+            mode,
+            board,
+            human_mark,
+            rules,
+            gravity,
+        })
+    }
+
+    /// Captures everything `to_json` saves, so the full history survives a round trip
+    #[cfg(feature = "serde")]
+    pub fn snapshot(&self) -> GameSnapshot {
+        GameSnapshot {
+            board: self.board.clone(),
+            to_move: self.to_move,
+            mode: self.mode,
+            human_mark: self.human_mark,
+            first_mark: self.first_mark,
+            one_based: self.one_based,
+            gravity: self.gravity,
+            early_draw: self.early_draw,
+            rules: self.rules,
+            moves: self.history.clone(),
+            resigned: self.resigned,
+            agreed_draw: self.agreed_draw,
+        }
+    }
+
+    /// Rebuilds a game from a snapshot, with a default `AiAgent`
+    /// The moves are replayed and must agree with the saved board and mark to move,
+    /// otherwise returns `GameError::InvalidSnapshot`
+    #[cfg(feature = "serde")]
+    pub fn from_snapshot(snapshot: GameSnapshot) -> Result<Game, GameError> {
+        if snapshot.human_mark == Cell::Empty
+            || snapshot.first_mark == Cell::Empty
+            || snapshot.resigned == Some(Cell::Empty)
+        {
+            return Err(GameError::InvalidSnapshot);
+        }
+
+        let mut game = Self::build(snapshot.mode, snapshot.human_mark, snapshot.first_mark);
+        game.board = Board::with_win_length(snapshot.board.size(), snapshot.board.win_length())
+            .map_err(|_| GameError::InvalidSnapshot)?;
+        game.one_based = snapshot.one_based;
+        game.gravity = snapshot.gravity;
+        game.early_draw = snapshot.early_draw;
+        game.set_rules(snapshot.rules);
+
+        for &(row, col, cell) in &snapshot.moves {
+            if cell != game.to_move || game.try_place(row, col).is_err() {
+                return Err(GameError::InvalidSnapshot);
+            }
+        }
+        game.resigned = snapshot.resigned;
+        game.agreed_draw = snapshot.agreed_draw;
+
+        if game.board != snapshot.board || game.to_move != snapshot.to_move {
+            return Err(GameError::InvalidSnapshot);
+        }
+        Ok(game)
+    }
+
+    /// Saves the game's `snapshot` as JSON
+    #[cfg(feature = "serde")]
+    pub fn to_json(&self) -> String {
+        serde_json::to_string(&self.snapshot()).expect("snapshots always serialize")
+    }
+
+    /// Restores a game saved by `to_json`, with a default `AiAgent`
+    /// Returns `GameError::InvalidSnapshot` if the JSON isn't a consistent snapshot
+    #[cfg(feature = "serde")]
+    pub fn from_json(input: &str) -> Result<Game, GameError> {
+        let snapshot = serde_json::from_str(input).map_err(|_| GameError::InvalidSnapshot)?;
+        Self::from_snapshot(snapshot)
+    }
+
+    /// Returns who controls the side playing `mark`
+    fn controller_of(&self, mark: Cell) -> Player {
+        match self.mode {
+            GameMode::HumanVsAi if mark == self.human_mark => Player::Human,
+            GameMode::HumanVsAi => Player::Ai,
+            GameMode::HumanVsHuman => Player::Human,
+            GameMode::AiVsAi => Player::Ai,
+        }
+    }
+
+    /// Checks if the game is over and returns the result
+    /// The winner is reported by controller, so when both sides share one, use `outcome`
+    pub fn check_game_over(&self) -> Option<GameResult> {
+        match self.outcome()? {
+            Outcome::Win(winner) => match self.player_of(winner)? {
+                Player::Human => Some(GameResult::HumanWin),
+                Player::Ai => Some(GameResult::AiWin),
+            },
+            Outcome::Draw => Some(GameResult::Draw),
+        }
+    }
+
+    /// Returns the board, the player to move, the result and the move count in one call
+    pub fn status(&self) -> GameStatus {
+        GameStatus {
+            board: self.board.clone(),
+            to_move: self.current_player(),
+            result: self.check_game_over(),
+            move_number: self.move_count(),
+        }
+    }
+
+    /// Ends the game with `player` forfeiting, so the other side wins; the board is unchanged
+    /// When `player` controls both sides, the side to move resigns
+    /// Does nothing once the game is over, or if `player` controls neither side
+    pub fn resign(&mut self, player: Player) {
+        let mark = self.mark_of(player);
+        if !self.is_over() && mark != Cell::Empty {
+            self.resigned = Some(mark);
+        }
+    }
+
+    /// Offers a draw, which stays open until it is accepted or the next move is made
+    /// Does nothing once the game is over
+    pub fn offer_draw(&mut self) {
+        if !self.is_over() {
+            self.draw_offered = true;
+        }
+    }
+
+    /// Accepts the pending draw offer, ending the game as a draw
+    /// Fails with `GameError::NoDrawOffer` if no draw has been offered since the last move
+    pub fn accept_draw(&mut self) -> Result<(), GameError> {
+        if self.is_over() {
+            return Err(GameError::GameOver);
+        }
+        if !self.draw_offered {
+            return Err(GameError::NoDrawOffer);
+        }
+        self.draw_offered = false;
+        self.agreed_draw = true;
+        Ok(())
+    }
+
+    /// Returns the winning mark or a draw once the game is over, whatever the mode
+    /// Under `RuleSet::Misere` the mark that completed a line is the loser
+    /// After a resignation the other mark wins
+    pub fn outcome(&self) -> Option<Outcome> {
+        if let Some(resigned) = self.resigned {
+            Some(Outcome::Win(resigned.opponent()))
+        } else if self.agreed_draw {
+            Some(Outcome::Draw)
+        } else if let Some(line_owner) = self.board.check_winner() {
+            match self.rules {
+                RuleSet::Standard => Some(Outcome::Win(line_owner)),
+                RuleSet::Misere => Some(Outcome::Win(line_owner.opponent())),
+            }
+        } else if self.board.is_full() || (self.early_draw && !self.board.has_winning_possibility())
+        {
+            Some(Outcome::Draw)
+        } else {
+            None
+        }
+    }
+
+    /// Like `check_game_over`, but first verifies the board could arise from legal play
+    /// Returns `GameError::IllegalState` for boards such as both players completing a line
+    pub fn checked_game_over(&self) -> Result<Option<GameResult>, GameError> {
+        if !self.board.is_legal_position() {
+            return Err(GameError::IllegalState);
+        }
+        Ok(self.check_game_over())
+    }
+
+    /// Resets the game to initial state
+    /// A finished game is added to the scoreboard, if one is kept, before the board clears
+    pub fn reset(&mut self) {
+        if let (Some(result), Some(scoreboard)) = (self.check_game_over(), &mut self.scoreboard) {
+            scoreboard.record(result);
+        }
+        self.board = self.empty_board();
+        self.to_move = self.first_mark;
+        self.history.clear();
+        self.redo_stack.clear();
+        self.resigned = None;
+        self.draw_offered = false;
+        self.agreed_draw = false;
+        self.rejected_human_moves = 0;
+        self.rejected_ai_moves = 0;
+    }
+
+    /// Starts a new game like `reset`, with the side that moved second last game moving first
+    /// Marks, mode and AI settings carry over, so repeated rematches alternate the opener
+    pub fn rematch(&mut self) {
+        self.first_mark = self.first_mark.opponent();
+        self.reset();
+    }
+}
+
+/// Collects the settings of a new game, checking they fit together when the game is built
+/// Unset options keep the defaults of `Game::new`
+#[derive(Debug, Clone)]
+pub struct GameBuilder {
+    board_size: usize,
+    win_length: Option<usize>,
+    difficulty: Difficulty,
+    seed: Option<u64>,
+    mode: GameMode,
+    human_mark: Cell,
+    rules: RuleSet,
+}
+
+impl GameBuilder {
+    /// Creates a builder for a standard 3x3 game against a `Difficulty::Hard` AI
+    pub fn new() -> Self {
+        Self {
+            board_size: Board::new().size(),
+            win_length: None,
+            difficulty: Difficulty::Hard,
+            seed: None,
+            mode: GameMode::HumanVsAi,
+            human_mark: Cell::X,
+            rules: RuleSet::Standard,
+        }
+    }
+
+    /// Sets the number of rows and columns; boards smaller than 3x3 are rejected by `build`
+    pub fn board_size(mut self, size: usize) -> Self {
+        self.board_size = size;
+        self
+    }
+
+    /// Sets how many marks in a row win; defaults to the board size
+    pub fn win_length(mut self, win_length: usize) -> Self {
+        self.win_length = Some(win_length);
+        self
+    }
+
+    /// Sets how strongly the AI plays
+    pub fn difficulty(mut self, difficulty: Difficulty) -> Self {
+        self.difficulty = difficulty;
+        self
+    }
+
+    /// Seeds the AI's random choices
+    pub fn seed(mut self, seed: u64) -> Self {
+        self.seed = Some(seed);
+        self
+    }
+
+    /// Sets who controls each side
+    pub fn mode(mut self, mode: GameMode) -> Self {
+        self.mode = mode;
+        self
+    }
+
+    /// Sets the human's mark in `GameMode::HumanVsAi`; X always moves first
+    pub fn human_mark(mut self, mark: Cell) -> Self {
+        self.human_mark = mark;
+        self
+    }
+
+    /// Sets whether completing a line wins or loses
+    pub fn ruleset(mut self, rules: RuleSet) -> Self {
+        self.rules = rules;
+        self
+    }
+
+    /// Creates the game, or returns `GameError::InvalidConfiguration` if the board is empty,
+    /// the win length is zero or longer than the board, or the human mark is `Cell::Empty`
+    pub fn build(self) -> Result<Game, GameError> {
+        let win_length = self.win_length.unwrap_or(self.board_size);
+        let board = Board::with_win_length(self.board_size, win_length)
+            .map_err(|_| GameError::InvalidConfiguration)?;
+        if self.human_mark == Cell::Empty {
+            return Err(GameError::InvalidConfiguration);
+        }
+
+        let mut ai_agent = match self.seed {
+            Some(seed) => AiAgent::with_seed(seed),
+            None => AiAgent::new(),
+        };
+        ai_agent.set_difficulty(self.difficulty);
+
+        let mut game = Game::build(self.mode, self.human_mark, Cell::X);
+        game.board = board;
+        game.ai_agent = ai_agent;
+        game.set_rules(self.rules);
+        Ok(game)
+    }
+}
+
+impl Default for GameBuilder {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl Default for Game {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+/// The settings named by a transcript's header line
+struct TranscriptHeader {
+    mode: GameMode,
+    board: Board,
+    human_mark: Cell,
+    rules: RuleSet,
+    gravity: bool,
+}
+
+impl Default for TranscriptHeader {
+    /// A 3x3 game under standard rules in the default mode, with the human as X
+    fn default() -> Self {
+        Self {
+            mode: GameMode::default(),
+            board: Board::new(),
+            human_mark: Cell::X,
+            rules: RuleSet::Standard,
+            gravity: false,
+        }
+    }
+}
+
+/// Names a column in a transcript: `a` to `z`, then `aa`, `ab`... like spreadsheet columns
+fn column_letters(col: usize) -> String {
+    let mut letters = Vec::new();
+    let mut remaining = col + 1;
+    while remaining > 0 {
+        remaining -= 1;
+        letters.push((b'a' + (remaining % 26) as u8) as char);
+        remaining /= 26;
+    }
+    letters.iter().rev().collect()
+}
+
+/// Reads a column named by `column_letters`, or None if `letters` is empty, not all
+/// lowercase letters, or too long to index a column
+fn parse_column_letters(letters: &str) -> Option<usize> {
+    if letters.is_empty() {
+        return None;
+    }
+    letters
+        .bytes()
+        .try_fold(0usize, |col, letter| {
+            if !letter.is_ascii_lowercase() {
+                return None;
+            }
+            col.checked_mul(26)?
+                .checked_add(usize::from(letter - b'a') + 1)
+        })
+        .map(|col| col - 1)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_new_game() {
+        let game = Game::new();
+        assert_eq!(game.current_player(), Player::Human);
+        assert!(game.check_game_over().is_none());
+    }
+
+    #[test]
+    fn test_human_move() {
+        let mut game = Game::new();
+        assert!(game.make_human_move(1, 1).is_ok());
+        assert_eq!(game.current_player(), Player::Ai);
+        assert_eq!(game.board().get(1, 1), Some(Cell::X));
+    }
+
+    #[test]
+    fn test_invalid_moves() {
+        let mut game = Game::new();
+
+        // Test invalid position
+        assert_eq!(game.make_human_move(3, 3), Err(GameError::InvalidPosition));
+
+        // Test occupied position
+        game.make_human_move(0, 0).unwrap();
+        game.make_ai_move().unwrap(); // Switch turns
+        assert_eq!(game.make_human_move(0, 0), Err(GameError::PositionOccupied));
+    }
+
+    #[test]
+    fn test_wrong_player_errors() {
+        let mut game = Game::new();
+
+        // Test human trying to move when it's AI's turn
+        game.make_human_move(1, 1).unwrap(); // Human moves first
+        assert_eq!(game.current_player(), Player::Ai);
+        assert_eq!(game.make_human_move(0, 0), Err(GameError::WrongPlayer));
+
+        // Test AI trying to move when it's human's turn
+        game.make_ai_move().unwrap(); // AI moves
+        assert_eq!(game.current_player(), Player::Human);
+        assert_eq!(game.make_ai_move(), Err(GameError::WrongPlayer));
+    }
+
+    #[test]
+    fn test_game_over_scenarios() {
+        // Manually set up a winning condition by directly manipulating the board
+        // This bypasses the AI logic and ensures we can test game over behavior
+        let mut board = Board::new();
+        board.set(0, 0, Cell::X);
+        board.set(0, 1, Cell::X);
+        board.set(0, 2, Cell::X); // X wins
+
+        // Create a new game with this winning board
+        let mut winning_game = Game::new();
+        winning_game.board = board;
 
         // Verify game is over
         assert_eq!(winning_game.check_game_over(), Some(GameResult::HumanWin));
@@ -265,9 +1463,8 @@ mod tests {
         while game.check_game_over().is_none() {
             if game.current_player() == Player::Human {
                 // Find any valid move for human
-                let empty_positions = game.board().empty_positions();
-                if let Some((row, col)) = empty_positions.first() {
-                    let _ = game.make_human_move(*row, *col);
+                if let Some((row, col)) = game.board().first_empty() {
+                    let _ = game.make_human_move(row, col);
                 }
             } else {
                 let _ = game.make_ai_move();
@@ -291,9 +1488,8 @@ mod tests {
         while game.check_game_over().is_none() && move_count < MAX_MOVES {
             if game.current_player() == Player::Human {
                 // Find first available empty position
-                let empty_positions = game.board().empty_positions();
-                if let Some((row, col)) = empty_positions.first() {
-                    if game.make_human_move(*row, *col).is_ok() {
+                if let Some((row, col)) = game.board().first_empty() {
+                    if game.make_human_move(row, col).is_ok() {
                         move_count += 1;
                     }
                 } else {
@@ -317,4 +1513,1256 @@ mod tests {
             assert!(game_result != GameResult::HumanWin);
         }
     }
+
+    #[test]
+    fn test_positions_follow_history() {
+        let mut game = Game::new();
+        assert_eq!(game.positions(), vec![Board::new()]);
+
+        game.make_human_move(0, 0).unwrap();
+        game.make_ai_move().unwrap();
+        game.make_human_move(2, 2).unwrap();
+
+        let positions = game.positions();
+        assert_eq!(positions.len(), 4);
+        assert_eq!(positions[0], Board::new());
+        assert_eq!(positions[1].get(0, 0), Some(Cell::X));
+        assert_eq!(positions[3], *game.board());
+    }
+
+    #[test]
+    fn test_postgame_review_suggests_better_move() {
+        let mut game = Game::new();
+        game.make_human_move(0, 1).unwrap();
+        game.make_ai_move().unwrap(); // AI takes the center
+                                      // Opposite edges let the AI build a fork; a corner would have held the draw
+        game.make_human_move(2, 1).unwrap();
+
+        let review = game.postgame_review();
+        assert_eq!(review.len(), 2);
+        assert_eq!(review[0], (0, MoveQuality::Best, None));
+        assert_eq!(review[1], (2, MoveQuality::Blunder, Some((0, 0))));
+    }
+
+    #[test]
+    fn test_auto_move_plays_both_sides_to_a_draw() {
+        let mut game = Game::new();
+
+        while game.check_game_over().is_none() {
+            let player = game.current_player();
+            let (row, col) = game.auto_move().unwrap();
+            let expected = if player == Player::Human {
+                Cell::X
+            } else {
+                Cell::O
+            };
+            assert_eq!(game.board().get(row, col), Some(expected));
+        }
+
+        assert_eq!(game.check_game_over(), Some(GameResult::Draw));
+        assert_eq!(game.auto_move(), Err(GameError::GameOver));
+    }
+
+    #[test]
+    fn test_expecting_follows_turn_protocol() {
+        let mut game = Game::new();
+        assert_eq!(game.expecting(), Some(Player::Human));
+
+        // Out-of-turn submission is rejected and doesn't change who is expected
+        assert_eq!(game.make_ai_move(), Err(GameError::WrongPlayer));
+        assert_eq!(game.expecting(), Some(Player::Human));
+
+        game.make_human_move(0, 0).unwrap();
+        assert_eq!(game.expecting(), Some(Player::Ai));
+
+        // A duplicate human submission is out of turn
+        assert_eq!(game.make_human_move(0, 0), Err(GameError::WrongPlayer));
+        assert_eq!(game.make_human_move(1, 1), Err(GameError::WrongPlayer));
+
+        game.make_ai_move().unwrap();
+        assert_eq!(game.expecting(), Some(Player::Human));
+        assert_eq!(game.make_human_move(0, 0), Err(GameError::PositionOccupied));
+
+        while game.check_game_over().is_none() {
+            game.auto_move().unwrap();
+        }
+        assert_eq!(game.expecting(), None);
+    }
+
+    #[test]
+    fn test_one_based_coordinates() {
+        let mut game = Game::new();
+        assert_eq!(game.parse_move("1 1"), Some((1, 1)));
+        assert_eq!(game.parse_move("3 0"), None);
+
+        game.set_one_based(true);
+        assert!(game.is_one_based());
+        assert_eq!(game.parse_move("2 2"), Some((1, 1)));
+        assert_eq!(game.parse_move("3 1"), Some((2, 0)));
+        assert_eq!(game.parse_move("0 1"), None);
+        assert_eq!(game.parse_move("4 1"), None);
+        assert_eq!(game.parse_move("2"), None);
+        assert_eq!(game.parse_move("a b"), None);
+
+        assert!(game
+            .board()
+            .render_with_origin(game.is_one_based())
+            .starts_with("  1   2   3"));
+    }
+
+    #[test]
+    fn test_human_move_with_move_type() {
+        let mut game = Game::new();
+        assert!(game.make_human_move_at(Move::new(0, 2)).is_ok());
+        assert_eq!(game.board().get(0, 2), Some(Cell::X));
+
+        game.make_ai_move().unwrap();
+        assert_eq!(
+            game.make_human_move_at(Move::from_index(9, game.board().size())),
+            Err(GameError::InvalidPosition)
+        );
+        assert_eq!(
+            game.make_human_move_at((0, 2)),
+            Err(GameError::PositionOccupied)
+        );
+    }
+
+    #[test]
+    fn test_record_matches_history() {
+        let mut game = Game::new();
+        game.make_human_move(0, 0).unwrap();
+        game.make_ai_move().unwrap();
+
+        let record = game.record();
+        assert_eq!(record.len(), 2);
+        assert_eq!(record.moves()[0], (0, 0, Cell::X));
+        assert!(record.is_legal());
+        assert_eq!(record.final_board(), *game.board());
+    }
+
+    #[test]
+    fn test_rewind_to_ply_and_resume() {
+        let mut game = Game::new();
+        while game.check_game_over().is_none() {
+            game.auto_move().unwrap();
+        }
+        let finished = game.positions();
+
+        assert_eq!(game.rewind_to(finished.len()), Err(GameError::InvalidPly));
+
+        game.rewind_to(3).unwrap();
+        assert_eq!(*game.board(), finished[3]);
+        assert_eq!(game.record().len(), 3);
+        assert_eq!(game.current_player(), Player::Ai);
+        assert!(game.check_game_over().is_none());
+
+        // Resume play from the rewound position
+        game.make_ai_move().unwrap();
+        assert_eq!(game.current_player(), Player::Human);
+        assert_eq!(game.record().len(), 4);
+
+        game.rewind_to(0).unwrap();
+        assert_eq!(*game.board(), Board::new());
+        assert_eq!(game.current_player(), Player::Human);
+    }
+
+    #[test]
+    fn test_checked_game_over_rejects_double_win() {
+        let mut board = Board::new();
+        for col in 0..board.size() {
+            board.set(0, col, Cell::X);
+            board.set(2, col, Cell::O);
+        }
+
+        let mut game = Game::new();
+        game.board = board;
+
+        // The unchecked variant reports whichever line it finds first
+        assert_eq!(game.check_game_over(), Some(GameResult::HumanWin));
+        assert_eq!(game.checked_game_over(), Err(GameError::IllegalState));
+
+        let mut game = Game::new();
+        assert_eq!(game.checked_game_over(), Ok(None));
+        game.make_human_move(1, 1).unwrap();
+        assert_eq!(game.checked_game_over(), Ok(None));
+    }
+
+    #[test]
+    fn test_reward_for() {
+        assert_eq!(GameResult::HumanWin.reward_for(Player::Human), 1.0);
+        assert_eq!(GameResult::HumanWin.reward_for(Player::Ai), -1.0);
+        assert_eq!(GameResult::AiWin.reward_for(Player::Human), -1.0);
+        assert_eq!(GameResult::AiWin.reward_for(Player::Ai), 1.0);
+        assert_eq!(GameResult::Draw.reward_for(Player::Human), 0.0);
+        assert_eq!(GameResult::Draw.reward_for(Player::Ai), 0.0);
+    }
+
+    #[test]
+    fn test_rejected_moves_are_counted() {
+        let mut game = Game::new();
+
+        game.make_human_move(1, 1).unwrap();
+        assert_eq!(game.rejected_moves(Player::Human), 0);
+
+        // Out of turn
+        assert!(game.make_human_move(0, 0).is_err());
+        game.make_ai_move().unwrap();
+
+        // Occupied and out of bounds
+        assert!(game.make_human_move(1, 1).is_err());
+        assert!(game.make_human_move(5, 0).is_err());
+        assert_eq!(game.rejected_moves(Player::Human), 3);
+
+        // The AI moving out of turn counts against the AI only
+        assert!(game.make_ai_move().is_err());
+        assert_eq!(game.rejected_moves(Player::Ai), 1);
+        assert_eq!(game.rejected_moves(Player::Human), 3);
+
+        // Legal moves don't count
+        let (row, col) = game.board().first_empty().unwrap();
+        game.make_human_move(row, col).unwrap();
+        assert_eq!(game.rejected_moves(Player::Human), 3);
+
+        game.reset();
+        assert_eq!(game.rejected_moves(Player::Human), 0);
+    }
+
+    #[test]
+    fn test_human_about_to_be_forked() {
+        let mut game = Game::new();
+        assert_eq!(game.human_about_to_be_forked(), None);
+
+        game.apply_move(0, 0, Cell::X);
+        game.apply_move(1, 1, Cell::O);
+        game.apply_move(1, 2, Cell::X);
+        game.apply_move(2, 2, Cell::O);
+
+        // Taking (1, 0) lets O play (2, 0) and threaten both (0, 2) and (2, 1)
+        assert_eq!(game.human_about_to_be_forked(), Some((1, 0)));
+
+        // Occupying that corner first removes the fork
+        game.apply_move(2, 0, Cell::X);
+        assert_eq!(game.current_player(), Player::Ai);
+        assert_eq!(game.human_about_to_be_forked(), None);
+    }
+
+    #[test]
+    fn test_outcome_with_perfect_play() {
+        let mut game = Game::new();
+        assert_eq!(game.outcome_with_perfect_play(), Prognosis::Draw);
+
+        game.apply_move(0, 0, Cell::X);
+        game.apply_move(1, 1, Cell::O);
+        game.apply_move(2, 2, Cell::X);
+        game.apply_move(0, 2, Cell::O);
+        // X blocks at (2, 0) and threatens both (1, 0) and (2, 1)
+        assert_eq!(game.outcome_with_perfect_play(), Prognosis::WinForCurrent);
+
+        game.apply_move(2, 0, Cell::X);
+        assert_eq!(game.outcome_with_perfect_play(), Prognosis::LossForCurrent);
+
+        game.apply_move(1, 0, Cell::O);
+        game.apply_move(2, 1, Cell::X);
+        assert!(game.board().is_game_over());
+        assert_eq!(game.outcome_with_perfect_play(), Prognosis::LossForCurrent);
+    }
+
+    #[test]
+    fn test_scoreboard_tallies_rematches() {
+        let mut game = Game::new();
+        game.reset();
+        assert_eq!(game.scoreboard(), None);
+
+        game.track_score();
+        // The human wins the top row
+        for (row, col) in [(0, 0), (1, 0), (0, 1), (1, 1), (0, 2)] {
+            game.make_move(row, col).unwrap();
+        }
+        game.reset();
+        // An unfinished game isn't counted
+        game.make_move(1, 1).unwrap();
+        game.reset();
+
+        let scoreboard = game.scoreboard().unwrap();
+        assert_eq!(scoreboard.human_wins, 1);
+        assert_eq!(scoreboard.games_played(), 1);
+        assert_eq!(scoreboard.to_string(), "You 1 - 0 AI (Draws: 0)");
+    }
+
+    #[test]
+    fn test_full_board_is_over_not_ai_no_move() {
+        // Drawn board written directly, with the AI to move
+        let mut game = Game::with_options(Cell::X, false);
+        game.board = Board::from_str_grid("XOX\nXOO\nOXX").unwrap();
+        assert_eq!(game.current_player(), Player::Ai);
+
+        // Finished games are caught before the AI is asked, so the fault variant stays distinct
+        assert_eq!(game.make_ai_move(), Err(GameError::GameOver));
+        assert_eq!(game.auto_move(), Err(GameError::GameOver));
+        assert_ne!(
+            GameError::AiNoMove.to_string(),
+            GameError::GameOver.to_string()
+        );
+    }
+
+    #[test]
+    fn test_render_board_follows_coordinates() {
+        let mut game = Game::new();
+        game.make_human_move(1, 1).unwrap();
+        assert_eq!(game.render_board(), game.board().render());
+
+        game.set_one_based(true);
+        assert!(game.render_board().starts_with("  1   2   3\n"));
+    }
+
+    #[test]
+    fn test_on_move_reports_each_move() {
+        use std::cell::RefCell;
+        use std::rc::Rc;
+
+        let mut game = Game::new();
+        let seen = Rc::new(RefCell::new(Vec::new()));
+        let log = Rc::clone(&seen);
+        game.on_move(Box::new(move |player, row, col, cell| {
+            log.borrow_mut().push((player, row, col, cell));
+        }));
+        let count = Rc::new(RefCell::new(0));
+        let counter = Rc::clone(&count);
+        game.on_move(Box::new(move |_, _, _, _| *counter.borrow_mut() += 1));
+
+        game.make_human_move(1, 1).unwrap();
+        assert!(game.make_human_move(0, 0).is_err());
+        game.make_ai_move().unwrap();
+
+        let ai_move = game.history[1];
+        assert_eq!(
+            *seen.borrow(),
+            vec![
+                (Player::Human, 1, 1, Cell::X),
+                (Player::Ai, ai_move.0, ai_move.1, Cell::O)
+            ]
+        );
+        assert_eq!(*count.borrow(), 2);
+    }
+
+    #[test]
+    fn test_available_moves() {
+        let mut game = Game::with_mode(GameMode::HumanVsHuman);
+        assert_eq!(game.available_moves().len(), 9);
+        assert!(game.is_legal_move(2, 2));
+        assert!(!game.is_legal_move(3, 0));
+
+        game.make_move(0, 0).unwrap();
+        assert_eq!(game.available_moves().len(), 8);
+        assert!(!game.available_moves().contains(&(0, 0)));
+        assert!(!game.is_legal_move(0, 0));
+
+        // X wins the top row with four cells still empty
+        for (row, col) in [(1, 0), (0, 1), (1, 1), (0, 2)] {
+            game.make_move(row, col).unwrap();
+        }
+        assert_eq!(game.board().empty_positions().len(), 4);
+        assert!(game.available_moves().is_empty());
+        assert!(!game.is_legal_move(2, 2));
+    }
+
+    #[test]
+    fn test_make_human_move_detailed() {
+        let mut game = Game::new();
+        let outcome = game.make_human_move_detailed(0, 0).unwrap();
+        assert_eq!(
+            outcome,
+            MoveOutcome {
+                placed: Cell::X,
+                at: (0, 0),
+                result: None,
+                next_player: Player::Ai,
+            }
+        );
+        assert_eq!(
+            game.make_human_move_detailed(1, 1),
+            Err(GameError::WrongPlayer)
+        );
+
+        game.apply_move(1, 0, Cell::O);
+        game.make_human_move(0, 1).unwrap();
+        game.apply_move(1, 1, Cell::O);
+        let outcome = game.make_human_move_detailed(0, 2).unwrap();
+        assert_eq!(outcome.at, (0, 2));
+        assert_eq!(outcome.result, Some(GameResult::HumanWin));
+    }
+
+    #[test]
+    fn test_gravity_game_takes_columns() {
+        let mut game = Game::with_mode(GameMode::HumanVsHuman);
+        game.set_gravity(true);
+        assert!(game.has_gravity());
+
+        assert_eq!(game.drop_move(1), Ok(2));
+        assert_eq!(game.drop_move(1), Ok(1));
+        assert_eq!(game.parse_move("1"), Some((0, 1)));
+        assert_eq!(game.parse_move("1 1"), None);
+        assert_eq!(game.drop_move(1), Ok(0));
+
+        // The column is full and floating cells can't be played
+        assert_eq!(game.parse_move("1"), None);
+        assert_eq!(game.drop_move(1), Err(GameError::PositionOccupied));
+        assert_eq!(game.drop_move(3), Err(GameError::InvalidPosition));
+        assert_eq!(game.make_move(0, 0), Err(GameError::InvalidPosition));
+        assert!(!game.is_legal_move(0, 0));
+        assert_eq!(game.available_moves(), vec![(2, 0), (2, 2)]);
+
+        // The AI only ever plays landing cells, and the setting survives saving
+        let mut game = Game::with_mode(GameMode::AiVsAi);
+        game.set_gravity(true);
+        while game.expecting().is_some() {
+            let (row, col) = game.auto_move().unwrap();
+            assert!(row == 2 || game.board().get(row + 1, col) != Some(Cell::Empty));
+        }
+        #[cfg(feature = "serde")]
+        {
+            let restored = Game::from_json(&game.to_json()).unwrap();
+            assert!(restored.has_gravity());
+        }
+    }
+
+    #[test]
+    fn test_misere_rules() {
+        let mut game = Game::new();
+        game.set_rules(RuleSet::Misere);
+        assert_eq!(game.rules(), RuleSet::Misere);
+
+        // The human completes the top row and so loses
+        for (row, col, cell) in [
+            (0, 0, Cell::X),
+            (1, 0, Cell::O),
+            (0, 1, Cell::X),
+            (1, 1, Cell::O),
+            (0, 2, Cell::X),
+        ] {
+            game.apply_move(row, col, cell);
+        }
+        assert_eq!(game.outcome(), Some(Outcome::Win(Cell::O)));
+        assert_eq!(game.check_game_over(), Some(GameResult::AiWin));
+
+        #[cfg(feature = "serde")]
+        {
+            let restored = Game::from_json(&game.to_json()).unwrap();
+            assert_eq!(restored.rules(), RuleSet::Misere);
+            assert_eq!(restored.check_game_over(), Some(GameResult::AiWin));
+        }
+
+        // The AI turns down the line it would complete under standard rules
+        let mut game = Game::new();
+        for (row, col, cell) in [
+            (1, 0, Cell::X),
+            (0, 0, Cell::O),
+            (1, 1, Cell::X),
+            (0, 1, Cell::O),
+            (2, 0, Cell::X),
+        ] {
+            game.apply_move(row, col, cell);
+        }
+        game.set_rules(RuleSet::Misere);
+        game.make_ai_move().unwrap();
+        assert_ne!(game.board().get(0, 2), Some(Cell::O));
+    }
+
+    #[test]
+    fn test_from_moves_and_replay() {
+        let moves = [(1, 1), (0, 0), (2, 2), (0, 2), (0, 1)];
+        let game = Game::from_moves(&moves, GameMode::HumanVsHuman).unwrap();
+        assert_eq!(game.move_count(), 5);
+
+        let expected = [
+            "...\n.X.\n...",
+            "O..\n.X.\n...",
+            "O..\n.X.\n..X",
+            "O.O\n.X.\n..X",
+            "OXO\n.X.\n..X",
+        ];
+        let boards: Vec<String> = game.replay().map(|board| board.to_str_grid()).collect();
+        assert_eq!(boards, expected);
+        assert_eq!(game.replay().last().as_ref(), Some(game.board()));
+
+        assert_eq!(
+            Game::from_moves(&[(1, 1), (1, 1)], GameMode::AiVsAi).err(),
+            Some(GameError::PositionOccupied)
+        );
+        assert_eq!(
+            Game::from_moves(&[(0, 3)], GameMode::HumanVsAi).err(),
+            Some(GameError::InvalidPosition)
+        );
+        assert_eq!(Game::new().replay().count(), 0);
+    }
+
+    #[test]
+    fn test_transcript_round_trip() {
+        let game = Game::from_moves(
+            &[(1, 1), (0, 0), (2, 2), (0, 2), (0, 1)],
+            GameMode::HumanVsHuman,
+        )
+        .unwrap();
+        let transcript = game.transcript();
+        assert_eq!(
+            transcript,
+            "HumanVsHuman, 3x3, 3 in a row\n1. X b2  O a1\n2. X c3  O c1\n3. X b1"
+        );
+
+        let parsed = Game::from_transcript(&transcript).unwrap();
+        assert_eq!(parsed.record(), game.record());
+        assert_eq!(parsed.board(), game.board());
+
+        // Games O opens start with O
+        let mut game = Game::with_options(Cell::X, false);
+        game.make_ai_move().unwrap();
+        game.make_human_move(2, 0).unwrap();
+        let parsed = Game::from_transcript(&game.transcript()).unwrap();
+        assert_eq!(parsed.record(), game.record());
+
+        // Transcripts without a header are 3x3 games in the default mode
+        let parsed = Game::from_transcript("1. X b2  O a1").unwrap();
+        assert_eq!(parsed.mode(), GameMode::HumanVsAi);
+        assert_eq!(parsed.move_count(), 2);
+        assert_eq!(Game::from_transcript("").unwrap().move_count(), 0);
+        for bad in [
+            "1. X b2  X a1",
+            "1. X b2  O b2",
+            "1. X d1",
+            "1. X",
+            "1. Q a1",
+            "1. X 2",
+            "HumanVsBots, 3x3, 3 in a row\n1. X b2",
+            "HumanVsAi, 3x4, 3 in a row",
+            "HumanVsAi, 3x3, 4 in a row",
+            "HumanVsAi, 3x3, 3 in a row, human -",
+        ] {
+            assert_eq!(
+                Game::from_transcript(bad).err(),
+                Some(GameError::InvalidTranscript)
+            );
+        }
+    }
+
+    #[test]
+    fn test_transcript_keeps_board_and_mode() {
+        let mut game = Game::builder()
+            .board_size(4)
+            .win_length(3)
+            .mode(GameMode::HumanVsAi)
+            .human_mark(Cell::O)
+            .build()
+            .unwrap();
+        game.try_place(3, 3).unwrap();
+        game.try_place(0, 1).unwrap();
+        let transcript = game.transcript();
+        assert_eq!(
+            transcript,
+            "HumanVsAi, 4x4, 3 in a row, human O\n1. X d4  O b1"
+        );
+
+        let parsed = Game::from_transcript(&transcript).unwrap();
+        assert_eq!(parsed.mode(), GameMode::HumanVsAi);
+        assert_eq!(parsed.human_mark, Cell::O);
+        assert_eq!(parsed.board(), game.board());
+        assert_eq!(parsed.board().win_length(), 3);
+    }
+
+    #[test]
+    fn test_transcript_keeps_variants() {
+        // The human completes the top row, which loses under misère rules
+        let mut game = Game::new();
+        game.set_rules(RuleSet::Misere);
+        for (row, col) in [(0, 0), (1, 0), (0, 1), (1, 1), (0, 2)] {
+            game.try_place(row, col).unwrap();
+        }
+        let transcript = game.transcript();
+        assert!(transcript.starts_with("HumanVsAi, 3x3, 3 in a row, human X, misere\n"));
+        let parsed = Game::from_transcript(&transcript).unwrap();
+        assert_eq!(parsed.rules(), RuleSet::Misere);
+        assert_eq!(parsed.check_game_over(), Some(GameResult::AiWin));
+
+        let mut game = Game::new();
+        game.set_rules(RuleSet::Misere);
+        game.set_gravity(true);
+        game.try_place(2, 1).unwrap();
+        game.try_place(1, 1).unwrap();
+        let transcript = game.transcript();
+        assert_eq!(
+            transcript,
+            "HumanVsAi, 3x3, 3 in a row, human X, misere, gravity\n1. X b3  O b2"
+        );
+        let parsed = Game::from_transcript(&transcript).unwrap();
+        assert!(parsed.has_gravity());
+        assert_eq!(parsed.rules(), RuleSet::Misere);
+        assert_eq!(parsed.board(), game.board());
+
+        // Moves are replayed with gravity, and variants keep their order
+        assert!(Game::from_transcript("HumanVsHuman, 3x3, 3 in a row, gravity\n1. X b3").is_ok());
+        assert_eq!(
+            Game::from_transcript("HumanVsHuman, 3x3, 3 in a row, gravity\n1. X b1").err(),
+            Some(GameError::InvalidTranscript)
+        );
+        assert_eq!(
+            Game::from_transcript("HumanVsHuman, 3x3, 3 in a row, gravity, misere").err(),
+            Some(GameError::InvalidTranscript)
+        );
+    }
+
+    #[test]
+    fn test_column_letters() {
+        assert_eq!(column_letters(0), "a");
+        assert_eq!(column_letters(25), "z");
+        assert_eq!(column_letters(26), "aa");
+        assert_eq!(column_letters(27), "ab");
+        assert_eq!(column_letters(52), "ba");
+        for col in [0, 1, 25, 26, 701, 702, 10_000] {
+            assert_eq!(parse_column_letters(&column_letters(col)), Some(col));
+        }
+        assert_eq!(parse_column_letters(""), None);
+        assert_eq!(parse_column_letters("aB"), None);
+        assert_eq!(parse_column_letters(&"z".repeat(20)), None);
+    }
+
+    #[test]
+    fn test_builder() {
+        let mut game = Game::builder()
+            .board_size(4)
+            .win_length(3)
+            .difficulty(Difficulty::Easy)
+            .seed(9)
+            .mode(GameMode::HumanVsAi)
+            .human_mark(Cell::O)
+            .ruleset(RuleSet::Misere)
+            .build()
+            .unwrap();
+        assert_eq!(game.board().size(), 4);
+        assert_eq!(game.board().win_length(), 3);
+        assert_eq!(game.rules(), RuleSet::Misere);
+        assert_eq!(game.current_player(), Player::Ai);
+
+        // The settings carry through play, resets and saves
+        game.make_ai_move().unwrap();
+        game.make_human_move(3, 3).unwrap();
+        #[cfg(feature = "serde")]
+        assert_eq!(
+            Game::from_json(&game.to_json()).unwrap().board(),
+            game.board()
+        );
+        game.reset();
+        assert_eq!(*game.board(), Board::with_win_length(4, 3).unwrap());
+
+        let default = GameBuilder::default().build().unwrap();
+        assert_eq!(*default.board(), Board::new());
+        assert_eq!(default.mode(), GameMode::HumanVsAi);
+    }
+
+    #[test]
+    fn test_builder_rejects_inconsistent_settings() {
+        let rejected = |builder: GameBuilder| builder.build().err();
+        assert_eq!(
+            rejected(Game::builder().board_size(3).win_length(4)),
+            Some(GameError::InvalidConfiguration)
+        );
+        assert_eq!(
+            rejected(Game::builder().win_length(0)),
+            Some(GameError::InvalidConfiguration)
+        );
+        assert_eq!(
+            rejected(Game::builder().board_size(0)),
+            Some(GameError::InvalidConfiguration)
+        );
+        assert_eq!(
+            rejected(Game::builder().board_size(2)),
+            Some(GameError::InvalidConfiguration)
+        );
+        assert_eq!(
+            rejected(Game::builder().human_mark(Cell::Empty)),
+            Some(GameError::InvalidConfiguration)
+        );
+    }
+
+    #[test]
+    fn test_hint() {
+        let mut game = Game::new();
+        game.apply_move(0, 0, Cell::X);
+        game.apply_move(1, 1, Cell::O);
+        game.apply_move(0, 1, Cell::X);
+        game.apply_move(2, 2, Cell::O);
+
+        // The human can complete the top row
+        assert_eq!(game.hint(), Some((0, 2)));
+        let board_before = game.board().clone();
+        assert_eq!(game.hint(), Some((0, 2)));
+        assert_eq!(game.board(), &board_before);
+
+        // The human has no win and must stop the middle column
+        let mut game = Game::new();
+        game.apply_move(0, 0, Cell::X);
+        game.apply_move(1, 1, Cell::O);
+        game.apply_move(2, 2, Cell::X);
+        game.apply_move(0, 1, Cell::O);
+        assert_eq!(game.hint(), Some((2, 1)));
+
+        // Nothing to suggest while the AI is to move
+        game.apply_move(2, 1, Cell::X);
+        assert_eq!(game.hint(), None);
+
+        // Hints follow the human's mark
+        let mut game = Game::with_options(Cell::O, false);
+        game.make_ai_move().unwrap();
+        assert!(game.hint().is_some());
+    }
+
+    #[test]
+    fn test_undo() {
+        let mut game = Game::new();
+        assert_eq!(game.undo(), Err(GameError::NothingToUndo));
+        assert_eq!(game.undo_full_turn(), Err(GameError::NothingToUndo));
+
+        game.make_human_move(0, 0).unwrap();
+        game.make_ai_move().unwrap();
+        assert_eq!(game.move_count(), 2);
+
+        game.undo().unwrap();
+        assert_eq!(game.move_count(), 1);
+        assert_eq!(game.current_player(), Player::Ai);
+        assert_eq!(game.board().get(0, 0), Some(Cell::X));
+
+        game.undo().unwrap();
+        assert_eq!(game.move_count(), 0);
+        assert_eq!(game.current_player(), Player::Human);
+        assert_eq!(*game.board(), Board::new());
+    }
+
+    #[test]
+    fn test_undo_reopens_finished_game() {
+        let mut game = Game::new();
+        game.apply_move(0, 0, Cell::X);
+        game.apply_move(1, 0, Cell::O);
+        game.apply_move(0, 1, Cell::X);
+        game.apply_move(1, 1, Cell::O);
+        game.apply_move(0, 2, Cell::X);
+        assert_eq!(game.check_game_over(), Some(GameResult::HumanWin));
+
+        game.undo().unwrap();
+        assert_eq!(game.check_game_over(), None);
+        assert_eq!(game.current_player(), Player::Human);
+        assert!(game.make_human_move(2, 2).is_ok());
+    }
+
+    #[test]
+    fn test_undo_full_turn() {
+        let mut game = Game::new();
+        game.make_human_move(0, 0).unwrap();
+        game.make_ai_move().unwrap();
+        game.make_human_move(2, 2).unwrap();
+        game.make_ai_move().unwrap();
+
+        game.undo_full_turn().unwrap();
+        assert_eq!(game.move_count(), 2);
+        assert_eq!(game.current_player(), Player::Human);
+
+        // Only the human's move is pending, so a single move is taken back
+        game.make_human_move(2, 2).unwrap();
+        game.undo_full_turn().unwrap();
+        assert_eq!(game.move_count(), 2);
+        assert_eq!(game.current_player(), Player::Human);
+    }
+
+    #[test]
+    fn test_redo_restores_undone_moves() {
+        let mut game = Game::new();
+        assert_eq!(game.redo(), Err(GameError::NothingToRedo));
+
+        game.make_human_move(0, 0).unwrap();
+        game.make_ai_move().unwrap();
+        let board = game.board().clone();
+
+        game.undo_full_turn().unwrap();
+        game.redo().unwrap();
+        assert_eq!(game.current_player(), Player::Ai);
+        game.redo().unwrap();
+        assert_eq!(*game.board(), board);
+        assert_eq!(game.current_player(), Player::Human);
+        assert_eq!(game.redo(), Err(GameError::NothingToRedo));
+    }
+
+    #[test]
+    fn test_new_move_clears_redo() {
+        let mut game = Game::new();
+        game.make_human_move(0, 0).unwrap();
+        game.make_ai_move().unwrap();
+
+        game.undo_full_turn().unwrap();
+        game.make_human_move(2, 2).unwrap();
+        assert_eq!(game.redo(), Err(GameError::NothingToRedo));
+        assert_eq!(game.board().get(0, 0), Some(Cell::Empty));
+    }
+
+    #[test]
+    fn test_human_plays_o_and_wins() {
+        let mut game = Game::with_options(Cell::O, false);
+        assert_eq!(game.current_player(), Player::Ai);
+        assert_eq!(game.make_human_move(0, 0), Err(GameError::WrongPlayer));
+
+        game.apply_move(0, 0, Cell::X);
+        game.apply_move(1, 0, Cell::O);
+        game.apply_move(2, 2, Cell::X);
+        game.apply_move(1, 1, Cell::O);
+        game.apply_move(0, 2, Cell::X);
+
+        game.make_human_move(1, 2).unwrap();
+        assert_eq!(game.board().get(1, 2), Some(Cell::O));
+        assert_eq!(game.check_game_over(), Some(GameResult::HumanWin));
+    }
+
+    #[test]
+    fn test_ai_moves_first_with_other_mark() {
+        let mut game = Game::with_options(Cell::O, false);
+        game.make_ai_move().unwrap();
+        assert_eq!(game.board().empty_positions().len(), 8);
+        assert!(game.board().occupancy(Cell::X) != 0);
+        assert_eq!(game.current_player(), Player::Human);
+
+        game.reset();
+        assert_eq!(game.current_player(), Player::Ai);
+
+        // The human can keep X but still move second
+        let mut game = Game::with_options(Cell::X, false);
+        game.make_ai_move().unwrap();
+        assert!(game.board().occupancy(Cell::O) != 0);
+        game.undo().unwrap();
+        assert_eq!(game.current_player(), Player::Ai);
+    }
+
+    #[test]
+    fn test_human_vs_human() {
+        let mut game = Game::with_mode(GameMode::HumanVsHuman);
+        assert_eq!(game.mode(), GameMode::HumanVsHuman);
+        assert_eq!(game.current_player(), Player::Human);
+        assert_eq!(game.make_ai_move(), Err(GameError::WrongPlayer));
+
+        for (row, col) in [(0, 0), (1, 0), (0, 1), (1, 1)] {
+            game.make_move(row, col).unwrap();
+            assert_eq!(game.current_player(), Player::Human);
+        }
+        assert_eq!(game.board().get(1, 1), Some(Cell::O));
+
+        // Human moves are accepted for either side too
+        game.make_human_move(0, 2).unwrap();
+        assert_eq!(game.outcome(), Some(Outcome::Win(Cell::X)));
+        assert_eq!(game.human_about_to_be_forked(), None);
+        assert_eq!(game.make_move(2, 2), Err(GameError::GameOver));
+    }
+
+    #[test]
+    fn test_ai_vs_ai() {
+        let mut game = Game::with_mode(GameMode::AiVsAi);
+        assert_eq!(game.make_human_move(0, 0), Err(GameError::WrongPlayer));
+
+        while game.expecting().is_some() {
+            assert_eq!(game.current_player(), Player::Ai);
+            game.make_ai_move().unwrap();
+        }
+        assert_eq!(game.outcome(), Some(Outcome::Draw));
+        assert_eq!(game.check_game_over(), Some(GameResult::Draw));
+        assert_eq!(game.move_count(), 9);
+    }
+
+    #[test]
+    fn test_outcome_names_the_winning_mark() {
+        let mut game = Game::with_options(Cell::O, true);
+        assert_eq!(game.outcome(), None);
+        game.apply_move(0, 0, Cell::O);
+        game.apply_move(1, 0, Cell::X);
+        game.apply_move(0, 1, Cell::O);
+        game.apply_move(1, 1, Cell::X);
+        game.apply_move(0, 2, Cell::O);
+        assert_eq!(game.outcome(), Some(Outcome::Win(Cell::O)));
+        assert_eq!(game.check_game_over(), Some(GameResult::HumanWin));
+    }
+
+    #[test]
+    fn test_player_other() {
+        assert_eq!(Player::Human.other(), Player::Ai);
+        assert_eq!(Player::Ai.other(), Player::Human);
+    }
+
+    #[test]
+    fn test_mark_and_player_conversions() {
+        let game = Game::new();
+        assert_eq!(game.mark_of(Player::Human), Cell::X);
+        assert_eq!(game.mark_of(Player::Ai), Cell::O);
+        assert_eq!(game.player_of(Cell::X), Some(Player::Human));
+        assert_eq!(game.player_of(Cell::O), Some(Player::Ai));
+        assert_eq!(game.player_of(Cell::Empty), None);
+
+        let mut swapped = Game::with_options(Cell::O, false);
+        assert_eq!(swapped.mark_of(Player::Human), Cell::O);
+        assert_eq!(swapped.mark_of(Player::Ai), Cell::X);
+        assert_eq!(swapped.player_of(Cell::X), Some(Player::Ai));
+        assert_eq!(swapped.player_of(Cell::O), Some(Player::Human));
+
+        // The AI opens with X, then only the human may move
+        assert_eq!(swapped.make_human_move(0, 0), Err(GameError::WrongPlayer));
+        swapped.make_ai_move().unwrap();
+        assert_eq!(swapped.board().count(Cell::X), 1);
+        assert_eq!(swapped.make_ai_move(), Err(GameError::WrongPlayer));
+
+        // With both sides human, the human places whichever mark is to move
+        let mut shared = Game::with_mode(GameMode::HumanVsHuman);
+        assert_eq!(shared.mark_of(Player::Ai), Cell::Empty);
+        shared.make_human_move(0, 0).unwrap();
+        assert_eq!(shared.mark_of(Player::Human), Cell::O);
+    }
+
+    #[test]
+    fn test_resign() {
+        let mut game = Game::new();
+        game.make_human_move(0, 0).unwrap();
+        game.make_ai_move().unwrap();
+        let board = game.board().clone();
+
+        game.resign(Player::Human);
+        assert_eq!(game.check_game_over(), Some(GameResult::AiWin));
+        assert_eq!(game.outcome(), Some(Outcome::Win(Cell::O)));
+        assert_eq!(*game.board(), board);
+        assert_eq!(game.expecting(), None);
+        assert_eq!(game.make_human_move(2, 2), Err(GameError::GameOver));
+        assert_eq!(game.make_ai_move(), Err(GameError::GameOver));
+
+        // The resignation survives a save and is cleared by a new game
+        #[cfg(feature = "serde")]
+        assert_eq!(
+            Game::from_json(&game.to_json()).unwrap().check_game_over(),
+            Some(GameResult::AiWin)
+        );
+        game.reset();
+        assert_eq!(game.check_game_over(), None);
+
+        let mut game = Game::new();
+        game.resign(Player::Ai);
+        assert_eq!(game.check_game_over(), Some(GameResult::HumanWin));
+    }
+
+    #[test]
+    fn test_accept_offered_draw() {
+        let mut game = Game::with_mode(GameMode::HumanVsHuman);
+        game.make_move(1, 1).unwrap();
+        game.offer_draw();
+        assert_eq!(game.accept_draw(), Ok(()));
+        assert_eq!(game.check_game_over(), Some(GameResult::Draw));
+        assert_eq!(game.make_move(0, 0), Err(GameError::GameOver));
+
+        #[cfg(feature = "serde")]
+        assert_eq!(
+            Game::from_json(&game.to_json()).unwrap().check_game_over(),
+            Some(GameResult::Draw)
+        );
+    }
+
+    #[test]
+    fn test_accept_draw_without_offer() {
+        let mut game = Game::with_mode(GameMode::HumanVsHuman);
+        assert_eq!(game.accept_draw(), Err(GameError::NoDrawOffer));
+
+        // An offer lapses once the next move is made
+        game.offer_draw();
+        game.make_move(1, 1).unwrap();
+        assert_eq!(game.accept_draw(), Err(GameError::NoDrawOffer));
+        assert_eq!(game.check_game_over(), None);
+    }
+
+    #[test]
+    fn test_early_draw() {
+        // Every line of this 4x4 board holds both marks, with half the cells still empty
+        let mut game = Game::builder()
+            .board_size(4)
+            .mode(GameMode::HumanVsHuman)
+            .build()
+            .unwrap();
+        for (row, col) in [
+            (0, 0),
+            (0, 1),
+            (1, 3),
+            (1, 2),
+            (2, 1),
+            (2, 0),
+            (3, 2),
+            (3, 3),
+        ] {
+            game.make_move(row, col).unwrap();
+        }
+        assert_eq!(game.board().empty_count(), 8);
+        assert!(!game.has_early_draw());
+        assert_eq!(game.check_game_over(), None);
+
+        game.set_early_draw(true);
+        assert_eq!(game.check_game_over(), Some(GameResult::Draw));
+        assert_eq!(game.make_move(0, 2), Err(GameError::GameOver));
+        #[cfg(feature = "serde")]
+        assert_eq!(
+            Game::from_json(&game.to_json()).unwrap().check_game_over(),
+            Some(GameResult::Draw)
+        );
+    }
+
+    #[test]
+    fn test_apply_moves() {
+        let mut game = Game::with_mode(GameMode::HumanVsHuman);
+        let moves = [
+            (0, 0),
+            (1, 1),
+            (0, 1),
+            (0, 2),
+            (2, 0),
+            (1, 0),
+            (1, 2),
+            (2, 1),
+            (2, 2),
+        ];
+        assert_eq!(game.apply_moves(&moves), Ok(()));
+        assert_eq!(game.check_game_over(), Some(GameResult::Draw));
+
+        // The AI answers the first human move in the center before the second is played
+        let mut game = Game::new();
+        assert_eq!(game.apply_moves(&[(0, 0), (2, 2)]), Ok(()));
+        assert_eq!(game.board().get(1, 1), Some(Cell::O));
+        assert_eq!(game.move_count(), 3);
+        assert_eq!(game.current_player(), Player::Ai);
+    }
+
+    #[test]
+    fn test_apply_moves_stops_at_first_illegal_move() {
+        let mut game = Game::with_mode(GameMode::HumanVsHuman);
+        assert_eq!(
+            game.apply_moves(&[(0, 0), (1, 1), (0, 0), (2, 2)]),
+            Err((2, GameError::PositionOccupied))
+        );
+        assert_eq!(game.move_count(), 2);
+
+        let mut game = Game::new();
+        assert_eq!(
+            game.apply_moves(&[(0, 0), (3, 3)]),
+            Err((1, GameError::InvalidPosition))
+        );
+        assert_eq!(game.move_count(), 2);
+    }
+
+    #[test]
+    fn test_random_first_is_seeded() {
+        let first_players: Vec<Player> = (0..20)
+            .map(|seed| Game::with_random_first(seed).current_player())
+            .collect();
+        assert!(first_players.contains(&Player::Human));
+        assert!(first_players.contains(&Player::Ai));
+
+        for seed in 0..20 {
+            let mut game = Game::with_random_first(seed);
+            assert_eq!(game.current_player(), first_players[seed as usize]);
+            assert_eq!(game.move_count(), 0);
+            if game.current_player() == Player::Ai {
+                game.make_ai_move().unwrap();
+                assert_eq!(game.board().count(Cell::O), 1);
+            }
+        }
+    }
+
+    /// Plays the first empty cell in reading order
+    struct FirstEmptyStrategy;
+
+    impl Strategy for FirstEmptyStrategy {
+        fn choose_move(&mut self, board: &Board, _mark: Cell) -> Option<(usize, usize)> {
+            board.first_empty()
+        }
+    }
+
+    #[test]
+    fn test_custom_strategy_chooses_ai_moves() {
+        let mut game = Game::with_strategy(Box::new(FirstEmptyStrategy));
+        game.make_human_move(1, 1).unwrap();
+        game.make_ai_move().unwrap();
+        assert_eq!(game.board().get(0, 0), Some(Cell::O));
+
+        game.make_human_move(2, 2).unwrap();
+        assert_eq!(game.auto_move(), Ok((0, 1)));
+        assert_eq!(game.board().get(0, 1), Some(Cell::O));
+    }
+
+    /// Always plays the same cell, whether or not it is free
+    struct FixedStrategy(Option<(usize, usize)>);
+
+    impl Strategy for FixedStrategy {
+        fn choose_move(&mut self, _board: &Board, _mark: Cell) -> Option<(usize, usize)> {
+            self.0
+        }
+    }
+
+    #[test]
+    fn test_custom_strategy_errors_surface() {
+        let mut game = Game::with_strategy(Box::new(FixedStrategy(None)));
+        game.make_human_move(1, 1).unwrap();
+        assert_eq!(game.make_ai_move(), Err(GameError::AiNoMove));
+        assert_eq!(game.auto_move(), Err(GameError::AiNoMove));
+
+        let mut game = Game::with_strategy(Box::new(FixedStrategy(Some((1, 1)))));
+        game.make_human_move(1, 1).unwrap();
+        assert_eq!(game.make_ai_move(), Err(GameError::PositionOccupied));
+        assert_eq!(game.auto_move(), Err(GameError::PositionOccupied));
+        assert_eq!(game.move_count(), 1);
+        assert_eq!(game.current_player(), Player::Ai);
+    }
+
+    #[test]
+    fn test_rematch_alternates_first_player() {
+        let mut game = Game::new();
+        game.make_human_move(1, 1).unwrap();
+
+        game.rematch();
+        assert_eq!(game.move_count(), 0);
+        assert_eq!(game.current_player(), Player::Ai);
+        game.make_ai_move().unwrap();
+        assert_eq!(game.board().count(Cell::O), 1);
+
+        game.rematch();
+        assert_eq!(game.current_player(), Player::Human);
+        assert_eq!(game.mark_of(Player::Human), Cell::X);
+        assert_eq!(game.mode(), GameMode::HumanVsAi);
+    }
+
+    #[test]
+    fn test_status() {
+        let mut game = Game::new();
+        game.make_human_move(1, 1).unwrap();
+        game.make_ai_move().unwrap();
+
+        let status = game.status();
+        assert_eq!(status.board, *game.board());
+        assert_eq!(status.board.count(Cell::O), 1);
+        assert_eq!(status.to_move, Player::Human);
+        assert_eq!(status.result, None);
+        assert_eq!(status.move_number, 2);
+    }
+
+    #[test]
+    fn test_self_play_hard_agents_draw() {
+        let (outcome, record) = Game::self_play(AiAgent::new(), AiAgent::new());
+        assert_eq!(outcome, Outcome::Draw);
+        assert_eq!(record.len(), 9);
+        assert!(record.is_legal());
+    }
+
+    #[test]
+    fn test_self_play_hard_never_loses_to_easy() {
+        for seed in 0..5 {
+            let mut easy = AiAgent::with_seed(seed);
+            easy.set_difficulty(Difficulty::Easy);
+            let (outcome, record) = Game::self_play(AiAgent::new(), easy);
+            assert_ne!(outcome, Outcome::Win(Cell::O));
+            assert_eq!(
+                record.final_board().check_winner().is_some(),
+                outcome != Outcome::Draw
+            );
+        }
+    }
+
+    #[test]
+    #[cfg(feature = "serde")]
+    fn test_json_round_trip_mid_game() {
+        let mut game = Game::with_options(Cell::O, false);
+        game.set_one_based(true);
+        game.make_ai_move().unwrap();
+        game.make_human_move(0, 0).unwrap();
+
+        let json = game.to_json();
+        assert!(json.contains("\"to_move\":\"X\""));
+
+        let restored = Game::from_json(&json).unwrap();
+        assert_eq!(restored.board(), game.board());
+        assert_eq!(restored.current_player(), Player::Ai);
+        assert_eq!(restored.mode(), GameMode::HumanVsAi);
+        assert!(restored.is_one_based());
+        assert_eq!(restored.record(), game.record());
+        assert_eq!(restored.to_json(), json);
+    }
+
+    #[test]
+    #[cfg(feature = "serde")]
+    fn test_json_round_trip_other_modes() {
+        let mut game = Game::with_mode(GameMode::HumanVsHuman);
+        game.make_move(1, 1).unwrap();
+        let restored = Game::from_json(&game.to_json()).unwrap();
+        assert_eq!(restored.mode(), GameMode::HumanVsHuman);
+        assert_eq!(restored.board(), game.board());
+
+        let restored = Game::from_json(&Game::with_mode(GameMode::AiVsAi).to_json()).unwrap();
+        assert_eq!(restored.current_player(), Player::Ai);
+    }
+
+    #[test]
+    #[cfg(feature = "serde")]
+    fn test_serde_round_trip() {
+        // A mid-game snapshot keeps the board and whose turn it is
+        let mut game = Game::builder().board_size(4).win_length(3).build().unwrap();
+        game.make_human_move(1, 1).unwrap();
+        game.make_ai_move().unwrap();
+        game.make_human_move(2, 3).unwrap();
+        let json = serde_json::to_string(&game.snapshot()).unwrap();
+        let snapshot: GameSnapshot = serde_json::from_str(&json).unwrap();
+        assert_eq!(snapshot, game.snapshot());
+        let restored = Game::from_snapshot(snapshot).unwrap();
+        assert_eq!(restored.board(), game.board());
+        assert_eq!(restored.current_player(), Player::Ai);
+        assert_eq!(restored.to_move, Cell::O);
+
+        for player in [Player::Human, Player::Ai] {
+            let json = serde_json::to_string(&player).unwrap();
+            assert_eq!(serde_json::from_str::<Player>(&json).unwrap(), player);
+        }
+        for result in [GameResult::HumanWin, GameResult::AiWin, GameResult::Draw] {
+            let json = serde_json::to_string(&result).unwrap();
+            assert_eq!(serde_json::from_str::<GameResult>(&json).unwrap(), result);
+        }
+    }
+
+    #[test]
+    #[cfg(feature = "serde")]
+    fn test_from_snapshot_rejects_inconsistent_snapshots() {
+        let mut game = Game::new();
+        game.make_human_move(1, 1).unwrap();
+
+        assert_eq!(
+            Game::from_json("not json").err(),
+            Some(GameError::InvalidSnapshot)
+        );
+        assert_eq!(
+            Game::from_json("{}").err(),
+            Some(GameError::InvalidSnapshot)
+        );
+
+        // The board must agree with the moves
+        let mut snapshot = game.snapshot();
+        snapshot.board = Board::new();
+        assert_eq!(
+            Game::from_snapshot(snapshot).err(),
+            Some(GameError::InvalidSnapshot)
+        );
+
+        // So must the mark to move
+        let mut snapshot = game.snapshot();
+        snapshot.to_move = Cell::X;
+        assert_eq!(
+            Game::from_snapshot(snapshot).err(),
+            Some(GameError::InvalidSnapshot)
+        );
+
+        // Moves out of turn are rejected
+        let mut snapshot = game.snapshot();
+        snapshot.moves[0].2 = Cell::O;
+        assert_eq!(
+            Game::from_snapshot(snapshot).err(),
+            Some(GameError::InvalidSnapshot)
+        );
+    }
 }
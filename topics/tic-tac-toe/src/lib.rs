@@ -1,9 +1,61 @@
 //! Tic-Tac-Toe Game Library
+//!
+//! The board, AI, solver and record modules only need an allocator: build with
+//! `--no-default-features --features alloc` to use them without `std`
 
+#![cfg_attr(not(feature = "std"), no_std)]
+
+#[cfg(feature = "alloc")]
+extern crate alloc;
+
+#[cfg(feature = "alloc")]
 pub mod ai;
+#[cfg(feature = "alloc")]
 pub mod board;
+#[cfg(feature = "std")]
+pub mod database;
+#[cfg(feature = "std")]
 pub mod game;
+#[cfg(feature = "std")]
+mod opening;
+#[cfg(feature = "alloc")]
+pub mod record;
+#[cfg(feature = "alloc")]
+mod rng;
+#[cfg(feature = "std")]
+pub mod scoreboard;
+#[cfg(feature = "alloc")]
+pub mod solver;
+#[cfg(feature = "std")]
+pub mod strategy;
 
-pub use ai::AiAgent;
-pub use board::{Board, Cell};
-pub use game::{Game, GameError, GameResult, Player};
+#[cfg(feature = "std")]
+pub use ai::SearchStats;
+#[cfg(feature = "alloc")]
+pub use ai::{Advantage, AiAgent, Difficulty, MoveQuality, Position};
+#[cfg(feature = "alloc")]
+pub use board::{
+    Board, BoardError, Cell, LineInfo, Move, ParseBoardError, ParseCellError, RenderStyle, RuleSet,
+};
+#[cfg(feature = "std")]
+pub use database::{Entry, PositionDatabase};
+#[cfg(all(feature = "std", feature = "serde"))]
+pub use game::GameSnapshot;
+#[cfg(feature = "std")]
+pub use game::{
+    Game, GameBuilder, GameError, GameMode, GameResult, GameStatus, MoveListener, MoveOutcome,
+    Outcome, Player, Prognosis, ReviewEntry,
+};
+#[cfg(feature = "alloc")]
+pub use record::{EncodeError, GameRecord, ParseError};
+#[cfg(feature = "std")]
+pub use scoreboard::Scoreboard;
+#[cfg(feature = "std")]
+pub use solver::TranspositionTable;
+#[cfg(feature = "alloc")]
+pub use solver::{Misere, TwoPlayerGame};
+#[cfg(feature = "std")]
+pub use strategy::{
+    play_seeded_game, HeuristicStrategy, MinimaxStrategy, MirrorStrategy, RandomStrategy, Strategy,
+    WeightedRandomStrategy,
+};
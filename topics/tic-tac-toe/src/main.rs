@@ -3,24 +3,48 @@
 use std::io::{self, Write};
 use tic_tac_toe::Game;
 
-/// Board size constant
-const BOARD_SIZE: usize = 3;
-
 fn main() {
+    // Pass --one-based to label and enter coordinates from 1 instead of 0
+    let one_based = std::env::args().any(|arg| arg == "--one-based");
+    // Pass --gravity to drop marks into columns instead of placing them anywhere
+    let gravity = std::env::args().any(|arg| arg == "--gravity");
+    let origin = if one_based { 1 } else { 0 };
+
+    let mut game = Game::new();
+    game.set_one_based(one_based);
+    game.set_gravity(gravity);
+    let size = game.board().size();
+
     println!("🎮 Welcome to Tic-Tac-Toe!");
     println!("You are playing as 'X' against the AI 'O'");
-    println!("Enter your moves as coordinates (row, col) from 0-2");
-    println!("Example: '1 2' places your mark at row 1, column 2");
+    if gravity {
+        println!(
+            "Enter a column from {}-{}; your mark drops to its lowest empty row",
+            origin,
+            size - 1 + origin
+        );
+    } else {
+        println!(
+            "Enter your moves as coordinates (row, col) from {}-{}",
+            origin,
+            size - 1 + origin
+        );
+        println!(
+            "Example: '{} {}' places your mark at row {}, column {}",
+            1 + origin,
+            2 + origin,
+            1 + origin,
+            2 + origin
+        );
+    }
     println!();
 
-    let mut game = Game::new();
-
     loop {
         // Display the current board
         game.display_board();
 
         match game.current_player() {
-            tic_tac_toe::Player::Human => match get_human_move() {
+            tic_tac_toe::Player::Human => match get_human_move(&game) {
                 Some((row, col)) => match game.make_human_move(row, col) {
                     Ok(_) => {}
                     Err(e) => {
@@ -58,9 +82,16 @@ fn main() {
 }
 
 /// Get a move from the human player
-fn get_human_move() -> Option<(usize, usize)> {
+fn get_human_move(game: &Game) -> Option<(usize, usize)> {
+    let origin = if game.is_one_based() { 1 } else { 0 };
+    let size = game.board().size();
+
     loop {
-        print!("Enter your move (row col) or 'quit' to exit: ");
+        if game.has_gravity() {
+            print!("Enter your move (col) or 'quit' to exit: ");
+        } else {
+            print!("Enter your move (row col) or 'quit' to exit: ");
+        }
         io::stdout().flush().unwrap();
 
         let mut input = String::new();
@@ -72,22 +103,23 @@ fn get_human_move() -> Option<(usize, usize)> {
                     return None;
                 }
 
-                let parts: Vec<&str> = input.split_whitespace().collect();
-                if parts.len() != 2 {
-                    println!("❌ Please enter two numbers separated by a space (e.g., '1 2')");
-                    continue;
-                }
-
-                match (parts[0].parse::<usize>(), parts[1].parse::<usize>()) {
-                    (Ok(row), Ok(col)) => {
-                        if row < BOARD_SIZE && col < BOARD_SIZE {
-                            return Some((row, col));
-                        } else {
-                            println!("❌ Coordinates must be between 0 and {}", BOARD_SIZE - 1);
-                        }
+                match game.parse_move(input) {
+                    Some(position) => return Some(position),
+                    None if game.has_gravity() => {
+                        println!(
+                            "❌ Please enter a column between {} and {} that isn't full",
+                            origin,
+                            size - 1 + origin
+                        );
                     }
-                    _ => {
-                        println!("❌ Please enter valid numbers");
+                    None => {
+                        println!(
+                            "❌ Please enter two numbers between {} and {} separated by a space (e.g., '{} {}')",
+                            origin,
+                            size - 1 + origin,
+                            1 + origin,
+                            2 + origin
+                        );
                     }
                 }
             }
@@ -0,0 +1,95 @@
+//! Opening module - Book of instant replies for the first plies on the standard board
+
+use crate::board::{Board, Cell};
+use std::collections::HashMap;
+use std::sync::OnceLock;
+
+/// Book positions for X opening, up to symmetry, with the reply a full search plays
+/// The same replies are used with the marks swapped for games O opens
+const CANONICAL_LINES: [(&str, (usize, usize)); 4] = [
+    ("...\n...\n...", (1, 1)),
+    ("X..\n...\n...", (1, 1)),
+    (".X.\n...\n...", (1, 1)),
+    ("...\n.X.\n...", (0, 0)),
+];
+
+/// Best moves for every position within the first two plies of a 3x3 game
+pub(crate) struct OpeningBook {
+    moves: HashMap<(Board, Cell), (usize, usize)>,
+}
+
+impl OpeningBook {
+    /// Returns the book move for `mark` on `board`, or None if the position isn't in the book
+    pub(crate) fn lookup(board: &Board, mark: Cell) -> Option<(usize, usize)> {
+        Self::global().moves.get(&(board.clone(), mark)).copied()
+    }
+
+    /// Returns the shared book, building it on first use
+    fn global() -> &'static OpeningBook {
+        static BOOK: OnceLock<OpeningBook> = OnceLock::new();
+        BOOK.get_or_init(Self::build)
+    }
+
+    /// Expands every canonical line to all of its symmetric variants, for both marks
+    fn build() -> Self {
+        let mut moves = HashMap::new();
+        for (grid, reply) in CANONICAL_LINES {
+            for (first, second) in [(Cell::X, Cell::O), (Cell::O, Cell::X)] {
+                let grid = grid.replace('X', &first.to_string());
+                let board = Board::from_str_grid(&grid).expect("book grids are valid");
+                // The empty board is a first move for either mark, any other line a reply
+                let to_move = if board.plies_from_start() == 0 {
+                    first
+                } else {
+                    second
+                };
+
+                // A symmetric board reaches itself several times; keep the untransformed reply
+                for symmetry in 0..Board::SYMMETRY_COUNT {
+                    moves
+                        .entry((board.transformed(symmetry), to_move))
+                        .or_insert_with(|| board.transform_position(symmetry, reply));
+                }
+            }
+        }
+        Self { moves }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::ai::AiAgent;
+
+    #[test]
+    fn test_book_matches_full_search() {
+        let ai = AiAgent::new();
+        let mut positions = 0;
+        for first in [Cell::X, Cell::O] {
+            let empty = Board::new();
+            assert_eq!(
+                OpeningBook::lookup(&empty, first),
+                ai.get_best_move_for(&empty, first)
+            );
+
+            for (row, col) in empty.empty_positions() {
+                let mut board = Board::new();
+                board.set(row, col, first);
+                let reply = first.opponent();
+                assert_eq!(
+                    OpeningBook::lookup(&board, reply),
+                    ai.get_best_move_for(&board, reply)
+                );
+                // Out of turn and off-book positions aren't covered
+                assert_eq!(OpeningBook::lookup(&board, first), None);
+                positions += 1;
+            }
+        }
+        assert_eq!(positions, 18);
+        assert_eq!(OpeningBook::global().moves.len(), 20);
+        assert_eq!(
+            OpeningBook::lookup(&Board::with_size(4).unwrap(), Cell::X),
+            None
+        );
+    }
+}
@@ -0,0 +1,294 @@
+//! Record module - Move lists for storing and replaying games
+
+use crate::ai::AiAgent;
+use crate::board::{Board, Cell};
+use alloc::vec::Vec;
+use core::fmt;
+
+/// Bit set in an encoded move when O played it
+const O_BIT: u8 = 0b1_0000;
+/// Bits holding the cell index (row * 3 + col) of an encoded move
+const INDEX_MASK: u8 = 0b1111;
+
+/// Represents errors that can occur while decoding a record
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum ParseError {
+    /// A byte refers to a cell outside the board
+    InvalidIndex(u8),
+    /// A byte sets bits the encoding doesn't use
+    ReservedBits(u8),
+}
+
+impl fmt::Display for ParseError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            ParseError::InvalidIndex(byte) => {
+                write!(f, "Byte {:#04x} refers to a cell outside the board", byte)
+            }
+            ParseError::ReservedBits(byte) => {
+                write!(f, "Byte {:#04x} sets unused bits", byte)
+            }
+        }
+    }
+}
+
+impl core::error::Error for ParseError {}
+
+/// Represents errors that can occur while encoding a record
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum EncodeError {
+    /// The move at this index lies off a 3x3 board
+    OffBoard(usize),
+    /// The move at this index places `Cell::Empty`
+    EmptyMark(usize),
+}
+
+impl fmt::Display for EncodeError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            EncodeError::OffBoard(index) => {
+                write!(f, "Move {} lies off a 3x3 board", index)
+            }
+            EncodeError::EmptyMark(index) => {
+                write!(f, "Move {} places an empty cell", index)
+            }
+        }
+    }
+}
+
+impl core::error::Error for EncodeError {}
+
+/// The moves of a game in the order they were played
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct GameRecord {
+    moves: Vec<(usize, usize, Cell)>,
+}
+
+impl GameRecord {
+    /// Creates an empty record
+    pub fn new() -> Self {
+        Self { moves: Vec::new() }
+    }
+
+    /// Creates a record from moves given as (row, col, mark)
+    pub fn from_moves(moves: Vec<(usize, usize, Cell)>) -> Self {
+        Self { moves }
+    }
+
+    /// Appends a move to the record
+    pub fn push(&mut self, row: usize, col: usize, cell: Cell) {
+        self.moves.push((row, col, cell));
+    }
+
+    /// Removes and returns the last recorded move
+    pub fn pop(&mut self) -> Option<(usize, usize, Cell)> {
+        self.moves.pop()
+    }
+
+    /// Returns the recorded moves as (row, col, mark)
+    pub fn moves(&self) -> &[(usize, usize, Cell)] {
+        &self.moves
+    }
+
+    /// Returns the number of plies recorded
+    pub fn len(&self) -> usize {
+        self.moves.len()
+    }
+
+    /// Returns true if no moves have been recorded
+    pub fn is_empty(&self) -> bool {
+        self.moves.is_empty()
+    }
+
+    /// Encodes the record with one byte per move: the low four bits hold the cell index
+    /// (row * 3 + col) and bit 4 is set when O played the move
+    /// The encoding only covers 3x3 games: returns `EncodeError::OffBoard` for a move off a
+    /// 3x3 board and `EncodeError::EmptyMark` for a move placing no mark
+    pub fn to_bytes(&self) -> Result<Vec<u8>, EncodeError> {
+        self.moves
+            .iter()
+            .enumerate()
+            .map(|(index, &(row, col, cell))| {
+                if row >= 3 || col >= 3 {
+                    return Err(EncodeError::OffBoard(index));
+                }
+                let position = (row * 3 + col) as u8;
+                match cell {
+                    Cell::X => Ok(position),
+                    Cell::O => Ok(position | O_BIT),
+                    Cell::Empty => Err(EncodeError::EmptyMark(index)),
+                }
+            })
+            .collect()
+    }
+
+    /// Decodes a record produced by `to_bytes`
+    pub fn from_bytes(bytes: &[u8]) -> Result<GameRecord, ParseError> {
+        let mut moves = Vec::with_capacity(bytes.len());
+        for &byte in bytes {
+            if byte & !(O_BIT | INDEX_MASK) != 0 {
+                return Err(ParseError::ReservedBits(byte));
+            }
+
+            let index = (byte & INDEX_MASK) as usize;
+            if index >= 9 {
+                return Err(ParseError::InvalidIndex(byte));
+            }
+
+            let cell = if byte & O_BIT != 0 { Cell::O } else { Cell::X };
+            moves.push((index / 3, index % 3, cell));
+        }
+        Ok(GameRecord { moves })
+    }
+
+    /// Returns the board reached after playing every recorded move
+    pub fn final_board(&self) -> Board {
+        let mut board = Board::new();
+        for &(row, col, cell) in &self.moves {
+            board.set(row, col, cell);
+        }
+        board
+    }
+
+    /// Returns the game-theoretic value after each ply from `perspective`'s point of view:
+    /// 1 for a forced win, 0 for a draw, -1 for a forced loss
+    /// A sudden drop marks a blunder
+    pub fn outcome_timeline(&self, perspective: Cell) -> Vec<f32> {
+        let ai = AiAgent::new();
+        let mut board = Board::new();
+
+        self.moves
+            .iter()
+            .map(|&(row, col, cell)| {
+                board.set(row, col, cell);
+                let to_move = cell.opponent();
+                let value = ai.evaluate(&board, to_move).signum() as f32;
+                if to_move == perspective {
+                    value
+                } else {
+                    -value
+                }
+            })
+            .collect()
+    }
+
+    /// Returns true if the record describes a legal game: marks alternate,
+    /// every move lands on an empty cell, and nothing is played after the game ended
+    pub fn is_legal(&self) -> bool {
+        let mut board = Board::new();
+        let mut previous = Cell::Empty;
+
+        for &(row, col, cell) in &self.moves {
+            if cell == Cell::Empty || cell == previous || board.is_game_over() {
+                return false;
+            }
+            if !board.set(row, col, cell) {
+                return false;
+            }
+            previous = cell;
+        }
+
+        true
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use alloc::vec;
+
+    #[test]
+    fn test_record_replays_to_final_board() {
+        let mut record = GameRecord::new();
+        assert!(record.is_empty());
+
+        record.push(1, 1, Cell::X);
+        record.push(0, 0, Cell::O);
+        assert_eq!(record.len(), 2);
+
+        let board = record.final_board();
+        assert_eq!(board.get(1, 1), Some(Cell::X));
+        assert_eq!(board.get(0, 0), Some(Cell::O));
+        assert!(record.is_legal());
+    }
+
+    #[test]
+    fn test_illegal_records() {
+        // Same mark twice in a row
+        let record = GameRecord::from_moves(vec![(0, 0, Cell::X), (1, 1, Cell::X)]);
+        assert!(!record.is_legal());
+
+        // Occupied cell
+        let record = GameRecord::from_moves(vec![(0, 0, Cell::X), (0, 0, Cell::O)]);
+        assert!(!record.is_legal());
+
+        // Move after X completed the top row
+        let record = GameRecord::from_moves(vec![
+            (0, 0, Cell::X),
+            (1, 0, Cell::O),
+            (0, 1, Cell::X),
+            (1, 1, Cell::O),
+            (0, 2, Cell::X),
+            (1, 2, Cell::O),
+        ]);
+        assert!(!record.is_legal());
+    }
+
+    #[test]
+    fn test_bytes_round_trip() {
+        let record = GameRecord::from_moves(vec![
+            (1, 1, Cell::X),
+            (0, 0, Cell::O),
+            (2, 2, Cell::X),
+            (0, 2, Cell::O),
+        ]);
+
+        let bytes = record.to_bytes().unwrap();
+        assert_eq!(bytes, vec![0x04, 0x10, 0x08, 0x12]);
+        assert_eq!(GameRecord::from_bytes(&bytes), Ok(record));
+        assert_eq!(GameRecord::from_bytes(&[]), Ok(GameRecord::new()));
+    }
+
+    #[test]
+    fn test_to_bytes_rejects_unencodable_moves() {
+        // (0, 3) would otherwise encode as (1, 0)
+        let record = GameRecord::from_moves(vec![(1, 1, Cell::X), (0, 3, Cell::O)]);
+        assert_eq!(record.to_bytes(), Err(EncodeError::OffBoard(1)));
+        let record = GameRecord::from_moves(vec![(3, 3, Cell::X)]);
+        assert_eq!(record.to_bytes(), Err(EncodeError::OffBoard(0)));
+
+        // An empty mark would otherwise come back as an X move
+        let record = GameRecord::from_moves(vec![(1, 1, Cell::X), (0, 0, Cell::Empty)]);
+        assert_eq!(record.to_bytes(), Err(EncodeError::EmptyMark(1)));
+    }
+
+    #[test]
+    fn test_from_bytes_rejects_malformed_buffers() {
+        assert_eq!(
+            GameRecord::from_bytes(&[0x04, 0x09]),
+            Err(ParseError::InvalidIndex(0x09))
+        );
+        assert_eq!(
+            GameRecord::from_bytes(&[0x1f]),
+            Err(ParseError::InvalidIndex(0x1f))
+        );
+        assert_eq!(
+            GameRecord::from_bytes(&[0x40]),
+            Err(ParseError::ReservedBits(0x40))
+        );
+    }
+
+    #[test]
+    fn test_outcome_timeline_shows_blunder() {
+        // X's opposite-edge second move lets O build a fork
+        let record = GameRecord::from_moves(vec![
+            (0, 1, Cell::X),
+            (1, 1, Cell::O),
+            (2, 1, Cell::X),
+            (0, 0, Cell::O),
+        ]);
+
+        assert_eq!(record.outcome_timeline(Cell::X), vec![0.0, 0.0, -1.0, -1.0]);
+        assert_eq!(record.outcome_timeline(Cell::O), vec![0.0, 0.0, 1.0, 1.0]);
+    }
+}
@@ -0,0 +1,78 @@
+//! Scoreboard module - Running tally of results across a session of games
+
+use crate::game::GameResult;
+use std::fmt;
+
+/// Counts how many games the human won, the AI won, and were drawn
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct Scoreboard {
+    pub human_wins: u32,
+    pub ai_wins: u32,
+    pub draws: u32,
+}
+
+impl Scoreboard {
+    /// Creates a scoreboard with every count at zero
+    pub fn new() -> Self {
+        Self {
+            human_wins: 0,
+            ai_wins: 0,
+            draws: 0,
+        }
+    }
+
+    /// Adds a finished game to the tally
+    pub fn record(&mut self, result: GameResult) {
+        match result {
+            GameResult::HumanWin => self.human_wins += 1,
+            GameResult::AiWin => self.ai_wins += 1,
+            GameResult::Draw => self.draws += 1,
+        }
+    }
+
+    /// Returns the number of games recorded
+    pub fn games_played(&self) -> u32 {
+        self.human_wins + self.ai_wins + self.draws
+    }
+
+    /// Sets every count back to zero
+    pub fn reset(&mut self) {
+        *self = Self::new();
+    }
+}
+
+impl fmt::Display for Scoreboard {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "You {} - {} AI (Draws: {})",
+            self.human_wins, self.ai_wins, self.draws
+        )
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_record_results() {
+        let mut scoreboard = Scoreboard::new();
+        assert_eq!(scoreboard.to_string(), "You 0 - 0 AI (Draws: 0)");
+
+        scoreboard.record(GameResult::HumanWin);
+        scoreboard.record(GameResult::AiWin);
+        scoreboard.record(GameResult::HumanWin);
+        scoreboard.record(GameResult::Draw);
+        scoreboard.record(GameResult::Draw);
+
+        assert_eq!(scoreboard.human_wins, 2);
+        assert_eq!(scoreboard.ai_wins, 1);
+        assert_eq!(scoreboard.draws, 2);
+        assert_eq!(scoreboard.games_played(), 5);
+        assert_eq!(scoreboard.to_string(), "You 2 - 1 AI (Draws: 2)");
+
+        scoreboard.reset();
+        assert_eq!(scoreboard, Scoreboard::default());
+    }
+}
@@ -0,0 +1,352 @@
+//! Solver module - Generic minimax for two-player games
+
+use alloc::vec::Vec;
+#[cfg(feature = "std")]
+use core::hash::Hash;
+#[cfg(feature = "std")]
+use std::collections::HashMap;
+
+/// A deterministic two-player game with alternating turns and perfect information
+pub trait TwoPlayerGame: Sized {
+    /// A move that can be played in a position
+    type Move: Copy;
+
+    /// Returns the moves available to the player to move
+    fn moves(&self) -> Vec<Self::Move>;
+
+    /// Returns the position reached after the player to move plays `m`
+    fn apply(&self, m: Self::Move) -> Self;
+
+    /// Returns the value of a finished game from the perspective of the player to move,
+    /// or None while the game is still in progress
+    fn terminal_value(&self) -> Option<i32>;
+}
+
+/// Plays `G` with every result reversed: whoever would have won loses instead
+/// Turns a game into its misère variant, where completing a line loses
+pub struct Misere<G>(pub G);
+
+impl<G: TwoPlayerGame> TwoPlayerGame for Misere<G> {
+    type Move = G::Move;
+
+    fn moves(&self) -> Vec<G::Move> {
+        self.0.moves()
+    }
+
+    fn apply(&self, m: G::Move) -> Self {
+        Misere(self.0.apply(m))
+    }
+
+    fn terminal_value(&self) -> Option<i32> {
+        self.0.terminal_value().map(|value| -value)
+    }
+}
+
+/// Returns the value of `game` for the player to move, assuming perfect play from both sides
+pub fn minimax<G: TwoPlayerGame>(game: &G) -> i32 {
+    negamax(game, -i32::MAX, i32::MAX)
+}
+
+/// Scores every available move from the perspective of the player to move
+/// Higher scores are better for the player to move
+pub fn score_moves<G: TwoPlayerGame>(game: &G) -> Vec<(G::Move, i32)> {
+    game.moves()
+        .into_iter()
+        .map(|m| (m, -minimax(&game.apply(m))))
+        .collect()
+}
+
+/// Negamax search with alpha-beta pruning
+/// The result is exact when it falls strictly between `alpha` and `beta`,
+/// and only a bound otherwise
+pub fn negamax<G: TwoPlayerGame>(game: &G, mut alpha: i32, beta: i32) -> i32 {
+    if let Some(value) = game.terminal_value() {
+        return value;
+    }
+
+    let moves = game.moves();
+    if moves.is_empty() {
+        // A position without moves that isn't terminal is treated as a draw
+        return 0;
+    }
+
+    let mut best_score = -i32::MAX;
+    for m in moves {
+        let score = -negamax(&game.apply(m), -beta, -alpha);
+        best_score = best_score.max(score);
+        alpha = alpha.max(score);
+
+        // Alpha-beta pruning
+        if alpha >= beta {
+            break;
+        }
+    }
+
+    best_score
+}
+
+/// Negamax search with alpha-beta pruning that looks at most `depth` plies ahead
+/// Unfinished positions at the horizon are scored by `heuristic`, from the perspective
+/// of the player to move
+pub fn negamax_limited<G: TwoPlayerGame>(
+    game: &G,
+    depth: usize,
+    mut alpha: i32,
+    beta: i32,
+    heuristic: &impl Fn(&G) -> i32,
+) -> i32 {
+    if let Some(value) = game.terminal_value() {
+        return value;
+    }
+    if depth == 0 {
+        return heuristic(game);
+    }
+
+    let moves = game.moves();
+    if moves.is_empty() {
+        // A position without moves that isn't terminal is treated as a draw
+        return 0;
+    }
+
+    let mut best_score = -i32::MAX;
+    for m in moves {
+        let score = -negamax_limited(&game.apply(m), depth - 1, -beta, -alpha, heuristic);
+        best_score = best_score.max(score);
+        alpha = alpha.max(score);
+
+        // Alpha-beta pruning
+        if alpha >= beta {
+            break;
+        }
+    }
+
+    best_score
+}
+
+/// How a cached score relates to the true value of its position
+#[cfg(feature = "std")]
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Bound {
+    /// The search completed inside its window, so the score is the true value
+    Exact,
+    /// The search was cut off by beta; the true value is at least the score
+    Lower,
+    /// No move beat alpha; the true value is at most the score
+    Upper,
+}
+
+/// Scores of previously searched positions, so positions reached by different
+/// move orders are only searched once
+/// Needs the `std` feature, for its hash map
+#[cfg(feature = "std")]
+pub struct TranspositionTable<K> {
+    entries: HashMap<K, (i32, Bound)>,
+}
+
+#[cfg(feature = "std")]
+impl<K: Hash + Eq> TranspositionTable<K> {
+    /// Creates an empty table
+    pub fn new() -> Self {
+        Self {
+            entries: HashMap::new(),
+        }
+    }
+
+    /// Returns the number of cached positions
+    pub fn len(&self) -> usize {
+        self.entries.len()
+    }
+
+    /// Returns true if no positions are cached
+    pub fn is_empty(&self) -> bool {
+        self.entries.is_empty()
+    }
+
+    /// Forgets every cached position
+    pub fn clear(&mut self) {
+        self.entries.clear();
+    }
+}
+
+#[cfg(feature = "std")]
+impl<K: Hash + Eq> Default for TranspositionTable<K> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+/// Like `minimax`, but reuses and records scores in `table`
+/// `key` must give every distinct position its own key
+#[cfg(feature = "std")]
+pub fn minimax_cached<G, K>(
+    game: &G,
+    key: &impl Fn(&G) -> K,
+    table: &mut TranspositionTable<K>,
+) -> i32
+where
+    G: TwoPlayerGame,
+    K: Hash + Eq,
+{
+    negamax_cached(game, -i32::MAX, i32::MAX, key, table)
+}
+
+/// Like `negamax`, but reuses and records scores in `table`
+/// Scores found with a narrowed window are stored as bounds, so later searches
+/// with a different window stay exact
+#[cfg(feature = "std")]
+pub fn negamax_cached<G, K>(
+    game: &G,
+    mut alpha: i32,
+    mut beta: i32,
+    key: &impl Fn(&G) -> K,
+    table: &mut TranspositionTable<K>,
+) -> i32
+where
+    G: TwoPlayerGame,
+    K: Hash + Eq,
+{
+    if let Some(value) = game.terminal_value() {
+        return value;
+    }
+
+    let position_key = key(game);
+    if let Some(&(score, bound)) = table.entries.get(&position_key) {
+        match bound {
+            Bound::Exact => return score,
+            Bound::Lower => alpha = alpha.max(score),
+            Bound::Upper => beta = beta.min(score),
+        }
+        if alpha >= beta {
+            return score;
+        }
+    }
+
+    let moves = game.moves();
+    if moves.is_empty() {
+        // A position without moves that isn't terminal is treated as a draw
+        return 0;
+    }
+
+    let original_alpha = alpha;
+    let mut best_score = -i32::MAX;
+    for m in moves {
+        let score = -negamax_cached(&game.apply(m), -beta, -alpha, key, table);
+        best_score = best_score.max(score);
+        alpha = alpha.max(score);
+
+        // Alpha-beta pruning
+        if alpha >= beta {
+            break;
+        }
+    }
+
+    let bound = if best_score <= original_alpha {
+        Bound::Upper
+    } else if best_score >= beta {
+        Bound::Lower
+    } else {
+        Bound::Exact
+    };
+    table.entries.insert(position_key, (best_score, bound));
+    best_score
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use alloc::vec;
+
+    /// Subtraction game: take one or two stones, whoever takes the last stone wins
+    struct Pile(u32);
+
+    impl TwoPlayerGame for Pile {
+        type Move = u32;
+
+        fn moves(&self) -> Vec<u32> {
+            (1..=2).filter(|&take| take <= self.0).collect()
+        }
+
+        fn apply(&self, take: u32) -> Self {
+            Pile(self.0 - take)
+        }
+
+        fn terminal_value(&self) -> Option<i32> {
+            // The previous player took the last stone
+            if self.0 == 0 {
+                Some(-1)
+            } else {
+                None
+            }
+        }
+    }
+
+    #[test]
+    fn test_toy_game() {
+        // Multiples of three are lost for the player to move
+        assert_eq!(minimax(&Pile(3)), -1);
+        assert_eq!(minimax(&Pile(6)), -1);
+        assert_eq!(minimax(&Pile(4)), 1);
+        assert_eq!(minimax(&Pile(5)), 1);
+
+        // From four stones, only taking one leaves the opponent on a multiple of three
+        assert_eq!(score_moves(&Pile(4)), vec![(1, 1), (2, -1)]);
+    }
+
+    #[test]
+    #[cfg(feature = "std")]
+    fn test_cached_matches_plain_search() {
+        let mut table = TranspositionTable::new();
+        for stones in 0..20 {
+            let key = |pile: &Pile| pile.0;
+            assert_eq!(
+                minimax_cached(&Pile(stones), &key, &mut table),
+                minimax(&Pile(stones))
+            );
+        }
+        assert!(!table.is_empty());
+
+        // Narrow windows leave bounds behind that later full searches must not trust blindly
+        let mut table = TranspositionTable::new();
+        for stones in 0..20 {
+            negamax_cached(&Pile(stones), 0, 1, &|pile: &Pile| pile.0, &mut table);
+        }
+        for stones in 0..20 {
+            assert_eq!(
+                minimax_cached(&Pile(stones), &|pile: &Pile| pile.0, &mut table),
+                minimax(&Pile(stones))
+            );
+        }
+
+        table.clear();
+        assert_eq!(table.len(), 0);
+    }
+
+    #[test]
+    fn test_limited_search_uses_heuristic_at_horizon() {
+        let heuristic = |_: &Pile| 7;
+        // Deep enough to see the end of the game
+        assert_eq!(
+            negamax_limited(&Pile(4), 10, -i32::MAX, i32::MAX, &heuristic),
+            1
+        );
+        // No lookahead at all
+        assert_eq!(
+            negamax_limited(&Pile(4), 0, -i32::MAX, i32::MAX, &heuristic),
+            7
+        );
+        // Both replies to one stone leave an unfinished pile scored for the opponent
+        assert_eq!(
+            negamax_limited(&Pile(3), 1, -i32::MAX, i32::MAX, &heuristic),
+            -7
+        );
+    }
+
+    #[test]
+    fn test_misere_reverses_results() {
+        // Taking the last stone now loses, so one stone left is lost for the player to move
+        assert_eq!(minimax(&Misere(Pile(1))), -1);
+        assert_eq!(minimax(&Misere(Pile(2))), 1);
+        assert_eq!(minimax(&Misere(Pile(4))), -1);
+        assert_eq!(score_moves(&Misere(Pile(2))), vec![(1, 1), (2, -1)]);
+    }
+}
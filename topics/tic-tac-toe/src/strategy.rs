@@ -0,0 +1,489 @@
+//! Strategy module - Pluggable move selection and simulation helpers
+
+use crate::ai::AiAgent;
+use crate::board::{Board, Cell};
+use crate::game::GameResult;
+use crate::record::GameRecord;
+use crate::rng::Rng;
+
+/// A move-selection engine that can play either mark
+pub trait Strategy {
+    /// Chooses a move for `mark` on `board`
+    /// Returns None if no moves are available
+    fn choose_move(&mut self, board: &Board, mark: Cell) -> Option<(usize, usize)>;
+}
+
+impl Strategy for AiAgent {
+    fn choose_move(&mut self, board: &Board, mark: Cell) -> Option<(usize, usize)> {
+        self.get_best_move_for(board, mark)
+    }
+}
+
+/// Strategy that plays the minimax move of an `AiAgent`, the engine `Game` uses
+/// unless given another one with `Game::with_strategy`
+pub struct MinimaxStrategy {
+    ai: AiAgent,
+}
+
+impl MinimaxStrategy {
+    /// Creates a strategy backed by a `Difficulty::Hard` agent
+    pub fn new() -> Self {
+        Self::with_agent(AiAgent::new())
+    }
+
+    /// Creates a strategy that plays as `ai` would, at its difficulty and depth
+    pub fn with_agent(ai: AiAgent) -> Self {
+        Self { ai }
+    }
+}
+
+impl Default for MinimaxStrategy {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl Strategy for MinimaxStrategy {
+    fn choose_move(&mut self, board: &Board, mark: Cell) -> Option<(usize, usize)> {
+        self.ai.get_best_move_for(board, mark)
+    }
+}
+
+/// Strategy that plays a uniformly random empty cell
+pub struct RandomStrategy {
+    rng: Rng,
+}
+
+impl RandomStrategy {
+    /// Creates a random strategy; equal seeds produce equal move choices
+    pub fn new(seed: u64) -> Self {
+        Self {
+            rng: Rng::new(seed),
+        }
+    }
+}
+
+impl Strategy for RandomStrategy {
+    fn choose_move(&mut self, board: &Board, _mark: Cell) -> Option<(usize, usize)> {
+        let empty_positions = board.empty_positions();
+        if empty_positions.is_empty() {
+            return None;
+        }
+        Some(empty_positions[self.rng.below(empty_positions.len())])
+    }
+}
+
+/// Strategy that picks moves at random, weighted by their minimax outcome
+/// Winning moves are favored over drawing ones and losing moves are only played
+/// when nothing else is left, so it plays decently but misses wins
+pub struct WeightedRandomStrategy {
+    rng: Rng,
+    /// Scores the candidate moves
+    ai: AiAgent,
+}
+
+impl WeightedRandomStrategy {
+    /// Weight given to a move that forces a win
+    const WIN_WEIGHT: usize = 3;
+    /// Weight given to a move that holds a draw
+    const DRAW_WEIGHT: usize = 1;
+
+    /// Creates a weighted random strategy; equal seeds produce equal move choices
+    pub fn new(seed: u64) -> Self {
+        Self {
+            rng: Rng::new(seed),
+            ai: AiAgent::new(),
+        }
+    }
+}
+
+impl Strategy for WeightedRandomStrategy {
+    fn choose_move(&mut self, board: &Board, mark: Cell) -> Option<(usize, usize)> {
+        let scored_moves = self.ai.score_moves(board, mark);
+        if scored_moves.is_empty() {
+            return None;
+        }
+
+        let weights: Vec<usize> = scored_moves
+            .iter()
+            .map(|&(_, score)| match score.signum() {
+                1 => Self::WIN_WEIGHT,
+                0 => Self::DRAW_WEIGHT,
+                _ => 0,
+            })
+            .collect();
+
+        let total: usize = weights.iter().sum();
+        if total == 0 {
+            // Every move loses, so any of them will do
+            return Some(scored_moves[self.rng.below(scored_moves.len())].0);
+        }
+
+        let mut pick = self.rng.below(total);
+        for (&(position, _), weight) in scored_moves.iter().zip(weights) {
+            if pick < weight {
+                return Some(position);
+            }
+            pick -= weight;
+        }
+
+        None
+    }
+}
+
+/// Strategy that follows simple rules without searching ahead:
+/// take an immediate win, otherwise block the opponent's immediate win,
+/// otherwise prefer the center, then corners, then edges
+/// It can be beaten by a player who sets up a fork
+pub struct HeuristicStrategy;
+
+impl HeuristicStrategy {
+    /// Creates a heuristic strategy
+    pub fn new() -> Self {
+        Self
+    }
+}
+
+impl Default for HeuristicStrategy {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl Strategy for HeuristicStrategy {
+    fn choose_move(&mut self, board: &Board, mark: Cell) -> Option<(usize, usize)> {
+        let opponent = mark.opponent();
+
+        board
+            .winning_moves_for(mark)
+            .first()
+            .copied()
+            .or_else(|| board.winning_moves_for(opponent).first().copied())
+            .or_else(|| AiAgent::select_strategic_move(board.size(), &board.empty_positions()))
+    }
+}
+
+/// Strategy that answers the opponent's last move with its point-symmetric counterpart
+/// (reflected through the center), falling back to minimax when that cell is taken
+pub struct MirrorStrategy {
+    /// The board as this strategy left it after its own last move
+    previous: Board,
+    fallback: AiAgent,
+}
+
+impl MirrorStrategy {
+    /// Creates a mirror strategy
+    pub fn new() -> Self {
+        Self {
+            previous: Board::new(),
+            fallback: AiAgent::new(),
+        }
+    }
+
+    /// Returns the opponent's move since this strategy last played, if exactly one was made
+    fn opponent_last_move(&self, board: &Board, opponent: Cell) -> Option<(usize, usize)> {
+        let same_size = self.previous.size() == board.size();
+        let mut changes = self.previous.diff(board);
+        if !same_size
+            || changes
+                .iter()
+                .any(|&(_, _, before, _)| before != Cell::Empty)
+        {
+            // Marks disappeared or the board changed size, so this is a different game;
+            // compare against an empty board
+            let empty = board.cleared();
+            changes = empty.diff(board);
+        }
+
+        match changes.as_slice() {
+            [(row, col, _, after)] if *after == opponent => Some((*row, *col)),
+            _ => None,
+        }
+    }
+}
+
+impl Default for MirrorStrategy {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl Strategy for MirrorStrategy {
+    fn choose_move(&mut self, board: &Board, mark: Cell) -> Option<(usize, usize)> {
+        let opponent = mark.opponent();
+
+        let mirrored = self
+            .opponent_last_move(board, opponent)
+            .map(|(row, col)| (board.size() - 1 - row, board.size() - 1 - col))
+            .filter(|&(row, col)| board.is_empty(row, col));
+        let chosen = mirrored.or_else(|| self.fallback.get_best_move_for(board, mark));
+
+        self.previous = board.clone();
+        if let Some((row, col)) = chosen {
+            self.previous.set(row, col, mark);
+        }
+        chosen
+    }
+}
+
+/// Plays a full game between two strategies from an empty board, X moving first
+/// Returns the winning mark, or None for a draw
+pub fn play_match(x: &mut dyn Strategy, o: &mut dyn Strategy) -> Option<Cell> {
+    play_from(&Board::new(), Cell::X, x, o)
+}
+
+/// Plays out `board` between two strategies, starting with `to_move`
+/// Returns the winning mark, or None for a draw
+pub fn play_from(
+    board: &Board,
+    to_move: Cell,
+    x: &mut dyn Strategy,
+    o: &mut dyn Strategy,
+) -> Option<Cell> {
+    let mut board = board.clone();
+    play_recorded(&mut board, to_move, x, o);
+    board.check_winner()
+}
+
+/// Plays a reproducible game with the human modeled as a seeded `WeightedRandomStrategy`
+/// moving first as X, against `ai` playing O
+/// Returns the moves played and the result
+pub fn play_seeded_game(human_seed: u64, mut ai: AiAgent) -> (GameRecord, GameResult) {
+    let mut human = WeightedRandomStrategy::new(human_seed);
+    let mut board = Board::new();
+    let record = play_recorded(&mut board, Cell::X, &mut human, &mut ai);
+
+    let result = match board.check_winner() {
+        Some(Cell::X) => GameResult::HumanWin,
+        Some(_) => GameResult::AiWin,
+        None => GameResult::Draw,
+    };
+    (record, result)
+}
+
+/// Plays `board` to completion in place, starting with `to_move`, and returns the moves played
+fn play_recorded(
+    board: &mut Board,
+    to_move: Cell,
+    x: &mut dyn Strategy,
+    o: &mut dyn Strategy,
+) -> GameRecord {
+    let mut record = GameRecord::new();
+    let mut mark = to_move;
+
+    while !board.is_game_over() {
+        let strategy: &mut dyn Strategy = if mark == Cell::X { &mut *x } else { &mut *o };
+        let (row, col) = match strategy.choose_move(board, mark) {
+            Some(position) => position,
+            None => break,
+        };
+
+        // A strategy proposing an illegal move forfeits its turn rather than looping forever
+        if board.set(row, col, mark) {
+            record.push(row, col, mark);
+        }
+        mark = mark.opponent();
+    }
+
+    record
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_minimax_strategy_matches_agent() {
+        let board = Board::from_str_grid("XX.\n.O.\n...").unwrap();
+        let mut strategy = MinimaxStrategy::new();
+        assert_eq!(strategy.choose_move(&board, Cell::O), Some((0, 2)));
+        assert_eq!(
+            strategy.choose_move(&Board::new(), Cell::X),
+            AiAgent::new().get_best_move_for(&Board::new(), Cell::X)
+        );
+    }
+
+    #[test]
+    fn test_random_strategy_plays_empty_cells() {
+        let mut board = Board::new();
+        board.set(0, 0, Cell::X);
+        board.set(1, 1, Cell::O);
+
+        let mut strategy = RandomStrategy::new(1);
+        for _ in 0..20 {
+            let (row, col) = strategy.choose_move(&board, Cell::X).unwrap();
+            assert!(board.is_empty(row, col));
+        }
+    }
+
+    #[test]
+    fn test_weighted_random_never_loses_from_won_position() {
+        // X to move can complete the anti-diagonal at (0, 2)
+        let mut board = Board::new();
+        board.set(0, 0, Cell::X);
+        board.set(1, 1, Cell::X);
+        board.set(0, 1, Cell::O);
+        board.set(2, 2, Cell::O);
+        board.set(2, 0, Cell::X);
+        board.set(1, 0, Cell::O);
+
+        for seed in 0..50 {
+            let mut weighted = WeightedRandomStrategy::new(seed);
+            let mut random = RandomStrategy::new(seed + 1000);
+            let winner = play_from(&board, Cell::X, &mut weighted, &mut random);
+            assert_ne!(winner, Some(Cell::O), "seed {} lost a won position", seed);
+        }
+    }
+
+    #[test]
+    fn test_weighted_random_beats_random() {
+        let mut weighted_wins = 0;
+        let mut random_wins = 0;
+
+        for seed in 0..40 {
+            let mut weighted = WeightedRandomStrategy::new(seed);
+            let mut random = RandomStrategy::new(seed + 1000);
+
+            // Alternate who plays X so neither side always moves first
+            let (winner, weighted_mark) = if seed % 2 == 0 {
+                (play_match(&mut weighted, &mut random), Cell::X)
+            } else {
+                (play_match(&mut random, &mut weighted), Cell::O)
+            };
+
+            match winner {
+                Some(mark) if mark == weighted_mark => weighted_wins += 1,
+                Some(_) => random_wins += 1,
+                None => {}
+            }
+        }
+
+        assert_eq!(random_wins, 0);
+        assert!(weighted_wins > random_wins);
+    }
+
+    #[test]
+    fn test_play_seeded_game_is_reproducible() {
+        let first = play_seeded_game(7, AiAgent::new());
+        let second = play_seeded_game(7, AiAgent::new());
+        assert_eq!(first, second);
+        assert!(first.0.is_legal());
+
+        for seed in 0..10 {
+            let (record, result) = play_seeded_game(seed, AiAgent::new());
+            assert_ne!(result, GameResult::HumanWin);
+            assert_eq!(record.moves()[0].2, Cell::X);
+        }
+    }
+
+    #[test]
+    fn test_heuristic_takes_win_before_block() {
+        // O can win at (0, 2) and X threatens (1, 2)
+        let mut board = Board::new();
+        board.set(0, 0, Cell::O);
+        board.set(0, 1, Cell::O);
+        board.set(1, 0, Cell::X);
+        board.set(1, 1, Cell::X);
+        board.set(2, 2, Cell::X);
+
+        let mut strategy = HeuristicStrategy::new();
+        assert_eq!(strategy.choose_move(&board, Cell::O), Some((0, 2)));
+    }
+
+    #[test]
+    fn test_heuristic_blocks_threat() {
+        let mut board = Board::new();
+        board.set(0, 0, Cell::X);
+        board.set(0, 1, Cell::X);
+        board.set(1, 1, Cell::O);
+
+        let mut strategy = HeuristicStrategy::new();
+        assert_eq!(strategy.choose_move(&board, Cell::O), Some((0, 2)));
+    }
+
+    #[test]
+    fn test_heuristic_prefers_center_then_corners() {
+        let mut strategy = HeuristicStrategy::new();
+        assert_eq!(strategy.choose_move(&Board::new(), Cell::X), Some((1, 1)));
+
+        let mut board = Board::new();
+        board.set(1, 1, Cell::X);
+        assert_eq!(strategy.choose_move(&board, Cell::O), Some((0, 0)));
+    }
+
+    #[test]
+    fn test_heuristic_loses_to_fork() {
+        let mut strategy = HeuristicStrategy::new();
+        let mut board = Board::new();
+
+        board.set(0, 0, Cell::X);
+        let reply = strategy.choose_move(&board, Cell::O).unwrap();
+        assert_eq!(reply, (1, 1));
+        board.set(reply.0, reply.1, Cell::O);
+
+        // The opposite corner invites a corner reply, which walks into a fork
+        board.set(2, 2, Cell::X);
+        let reply = strategy.choose_move(&board, Cell::O).unwrap();
+        assert_eq!(reply, (0, 2));
+        board.set(reply.0, reply.1, Cell::O);
+
+        // Blocking at (2, 0) creates threats at both (1, 0) and (2, 1)
+        board.set(2, 0, Cell::X);
+        let reply = strategy.choose_move(&board, Cell::O).unwrap();
+        board.set(reply.0, reply.1, Cell::O);
+
+        let (row, col) = board.winning_moves_for(Cell::X)[0];
+        board.set(row, col, Cell::X);
+        assert_eq!(board.check_winner(), Some(Cell::X));
+    }
+
+    #[test]
+    fn test_mirror_strategy_reflects_last_move() {
+        let mut strategy = MirrorStrategy::new();
+        let mut board = Board::new();
+
+        board.set(0, 0, Cell::X);
+        assert_eq!(strategy.choose_move(&board, Cell::O), Some((2, 2)));
+        board.set(2, 2, Cell::O);
+
+        board.set(0, 1, Cell::X);
+        assert_eq!(strategy.choose_move(&board, Cell::O), Some((2, 1)));
+    }
+
+    #[test]
+    fn test_mirror_strategy_reflects_on_larger_boards() {
+        let mut strategy = MirrorStrategy::new();
+        let mut board = Board::with_size(4).unwrap();
+
+        board.set(0, 0, Cell::X);
+        assert_eq!(strategy.choose_move(&board, Cell::O), Some((3, 3)));
+        board.set(3, 3, Cell::O);
+
+        board.set(1, 2, Cell::X);
+        assert_eq!(strategy.choose_move(&board, Cell::O), Some((2, 1)));
+    }
+
+    #[test]
+    fn test_mirror_strategy_falls_back_when_occupied() {
+        let mut strategy = MirrorStrategy::new();
+
+        // The center mirrors onto itself, so minimax picks the reply
+        let mut board = Board::new();
+        board.set(1, 1, Cell::X);
+        assert_eq!(
+            strategy.choose_move(&board, Cell::O),
+            AiAgent::new().get_best_move(&board)
+        );
+
+        // The mirror of (2, 2) is already O's, so minimax picks another reply
+        let mut board = Board::new();
+        board.set(0, 0, Cell::O);
+        board.set(1, 1, Cell::X);
+        let mut strategy = MirrorStrategy::new();
+        strategy.previous = board.clone();
+        board.set(2, 2, Cell::X);
+        let chosen = strategy.choose_move(&board, Cell::O).unwrap();
+        assert_ne!(chosen, (0, 0));
+        assert_eq!(Some(chosen), AiAgent::new().get_best_move(&board));
+    }
+}